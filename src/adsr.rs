@@ -0,0 +1,277 @@
+use any_point::AnyPoint;
+use containers::VecEnvelope;
+use envelope_mut::EnvelopeMut;
+use shape::SegmentShape;
+
+
+/// The duration and curve of a single stage of an `Adsr`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ramp {
+    /// The duration of the stage in seconds.
+    pub duration: f32,
+    /// The curve along which the level moves over the stage.
+    pub shape: SegmentShape<f32>,
+}
+
+/// The stages through which an `Adsr` progresses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AdsrStage {
+    /// The gate is off and the release has finished, so the level is `0.0`.
+    Idle,
+    /// The level is held where it was when the gate opened.
+    Delay,
+    /// The level rises to `1.0`.
+    Attack,
+    /// The level is held at `1.0`.
+    Hold,
+    /// The level falls to the sustain level.
+    Decay,
+    /// The level is held at the sustain level until the gate closes.
+    Sustain,
+    /// The level falls from wherever it was when the gate closed to `0.0`.
+    Release,
+}
+
+/// An attack, decay, sustain and release envelope generator driven by a gate.
+///
+/// The optional delay and hold stages (see `Adsr::ahdsr` and `Adsr::dahdsr`) have a duration of
+/// `0.0` for a plain ADSR. Stages with a duration of `0.0` are skipped.
+///
+/// Each stage moves from the level at which it began, so opening the gate during the release
+/// attacks from the current level and closing the gate during the attack releases from the
+/// current level, rather than jumping.
+#[derive(Clone)]
+pub struct Adsr {
+    /// The duration of the delay stage in seconds.
+    pub delay: f32,
+    /// The rise to the peak level of `1.0`.
+    pub attack: Ramp,
+    /// The duration of the hold stage in seconds.
+    pub hold: f32,
+    /// The fall from the peak to the sustain level.
+    pub decay: Ramp,
+    /// The level at which the envelope rests while the gate is held, from `0.0` to `1.0`.
+    pub sustain: f32,
+    /// The fall to `0.0` once the gate closes.
+    pub release: Ramp,
+    sample_rate: f32,
+    stage: AdsrStage,
+    /// The number of samples produced since the start of the current stage.
+    stage_frame: u64,
+    /// The level at the start of the current stage.
+    stage_level: f32,
+    /// The most recently produced level.
+    level: f32,
+}
+
+
+impl Ramp {
+    /// Constructor for a Ramp.
+    #[inline]
+    pub fn new(duration: f32, shape: SegmentShape<f32>) -> Ramp {
+        Ramp {
+            duration,
+            shape,
+        }
+    }
+
+    /// A straight ramp lasting the given duration in seconds.
+    #[inline]
+    pub fn linear(duration: f32) -> Ramp {
+        Ramp::new(duration, SegmentShape::Linear)
+    }
+
+    /// The level at `secs` seconds into a ramp from `start` to `end`.
+    #[inline]
+    fn level(&self, start: f32, end: f32, secs: f32) -> f32 {
+        self.shape.y((0.0, start), (self.duration, end), secs)
+    }
+}
+
+
+impl Adsr {
+    /// An attack, decay, sustain and release envelope for the given sample rate.
+    pub fn new(sample_rate: f32, attack: Ramp, decay: Ramp, sustain: f32, release: Ramp) -> Self {
+        Adsr::dahdsr(sample_rate, 0.0, attack, 0.0, decay, sustain, release)
+    }
+
+    /// An attack, hold, decay, sustain and release envelope for the given sample rate.
+    pub fn ahdsr(sample_rate: f32,
+                 attack: Ramp,
+                 hold: f32,
+                 decay: Ramp,
+                 sustain: f32,
+                 release: Ramp) -> Self
+    {
+        Adsr::dahdsr(sample_rate, 0.0, attack, hold, decay, sustain, release)
+    }
+
+    /// A delay, attack, hold, decay, sustain and release envelope for the given sample rate.
+    pub fn dahdsr(sample_rate: f32,
+                  delay: f32,
+                  attack: Ramp,
+                  hold: f32,
+                  decay: Ramp,
+                  sustain: f32,
+                  release: Ramp) -> Self
+    {
+        Adsr {
+            delay,
+            attack,
+            hold,
+            decay,
+            sustain,
+            release,
+            sample_rate,
+            stage: AdsrStage::Idle,
+            stage_frame: 0,
+            stage_level: 0.0,
+            level: 0.0,
+        }
+    }
+
+    /// The sample rate at which the envelope is generated.
+    #[inline]
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Change the sample rate at which the envelope is generated.
+    ///
+    /// The position within the current stage is preserved in seconds.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        let secs = self.stage_frame as f32 / self.sample_rate;
+        self.stage_frame = (secs * sample_rate) as u64;
+        self.sample_rate = sample_rate;
+    }
+
+    /// The current stage of the envelope.
+    #[inline]
+    pub fn stage(&self) -> AdsrStage {
+        self.stage
+    }
+
+    /// The most recently produced level.
+    #[inline]
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Whether or not the envelope is producing anything other than silence.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.stage != AdsrStage::Idle
+    }
+
+    /// The curve produced by an idle envelope whose gate is held until it has sustained for the
+    /// given number of seconds, as an `Envelope` of levels over seconds.
+    ///
+    /// This is useful for drawing the envelope, or for evaluating it at arbitrary positions.
+    pub fn curve(&self, sustain_duration: f32) -> VecEnvelope<AnyPoint<f32, f32>> {
+        let stages = [
+            (self.delay, 0.0, self.attack.shape),
+            (self.attack.duration, 1.0, SegmentShape::Linear),
+            (self.hold, 1.0, self.decay.shape),
+            (self.decay.duration, self.sustain, SegmentShape::Linear),
+            (sustain_duration, self.sustain, self.release.shape),
+            (self.release.duration, 0.0, SegmentShape::Linear),
+        ];
+        let mut points = VecEnvelope::new();
+        points.insert_point(AnyPoint::new(0.0, 0.0, SegmentShape::Linear));
+        let mut x = 0.0;
+        for &(duration, y, shape) in &stages {
+            // A stage with no duration jumps straight to its level. The previous point is only
+            // replaced by the stage that follows it if both share the same level.
+            let last = points.as_slice().len() - 1;
+            if duration > 0.0 {
+                x += duration;
+            } else if points.as_slice()[last].y == y {
+                points.remove_point(last);
+            }
+            points.insert_point(AnyPoint::new(x, y, shape));
+        }
+        points
+    }
+
+    /// Open the gate, starting the envelope from its current level.
+    pub fn gate_on(&mut self) {
+        self.enter(AdsrStage::Delay);
+    }
+
+    /// Close the gate, releasing the envelope from its current level.
+    ///
+    /// Does nothing if the envelope is already releasing or idle.
+    pub fn gate_off(&mut self) {
+        match self.stage {
+            AdsrStage::Idle | AdsrStage::Release => (),
+            _ => self.enter(AdsrStage::Release),
+        }
+    }
+
+    /// Fill the given buffer with consecutive levels of the envelope.
+    pub fn fill(&mut self, out: &mut [f32]) {
+        for sample in out {
+            *sample = self.next_level();
+        }
+    }
+
+    /// Begin the given stage from the current level.
+    fn enter(&mut self, stage: AdsrStage) {
+        self.stage = stage;
+        self.stage_frame = 0;
+        self.stage_level = self.level;
+    }
+
+    /// Produce the level for the current sample and step forward by one sample.
+    fn next_level(&mut self) -> f32 {
+        loop {
+            let secs = self.stage_frame as f32 / self.sample_rate;
+            let (duration, next_stage) = match self.stage {
+                AdsrStage::Idle => return 0.0,
+                AdsrStage::Sustain => {
+                    self.level = self.sustain;
+                    return self.level;
+                },
+                AdsrStage::Delay => (self.delay, AdsrStage::Attack),
+                AdsrStage::Attack => (self.attack.duration, AdsrStage::Hold),
+                AdsrStage::Hold => (self.hold, AdsrStage::Decay),
+                AdsrStage::Decay => (self.decay.duration, AdsrStage::Sustain),
+                AdsrStage::Release => (self.release.duration, AdsrStage::Idle),
+            };
+
+            // Move on once the stage has finished, ending each stage exactly on its target.
+            if secs >= duration {
+                self.level = match self.stage {
+                    AdsrStage::Attack => 1.0,
+                    AdsrStage::Decay => self.sustain,
+                    AdsrStage::Release => 0.0,
+                    _ => self.level,
+                };
+                self.enter(next_stage);
+                continue;
+            }
+
+            let start = self.stage_level;
+            self.level = match self.stage {
+                AdsrStage::Attack => self.attack.level(start, 1.0, secs),
+                AdsrStage::Decay => self.decay.level(start, self.sustain, secs),
+                AdsrStage::Release => self.release.level(start, 0.0, secs),
+                _ => start,
+            };
+            self.stage_frame += 1;
+            return self.level;
+        }
+    }
+}
+
+
+impl Iterator for Adsr {
+    type Item = f32;
+    /// Yields the level of the envelope for each consecutive sample.
+    ///
+    /// This never returns `None`. An idle envelope yields `0.0`.
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        Some(self.next_level())
+    }
+}
@@ -0,0 +1,83 @@
+use interpolate;
+use interpolation::{Ease, Spatial};
+use num::{Float, NumCast};
+use point::{Point, PointMut};
+use shape::SegmentShape;
+
+
+/// A type whose segment may take any `SegmentShape`, allowing jumps, ramps, eased segments and
+/// curves to be mixed within a single envelope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnyPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float + Ease,
+{
+    pub x: X,
+    pub y: Y,
+    /// The shape of the segment from this point to the next.
+    pub shape: SegmentShape<Y::Scalar>,
+}
+
+
+impl<X, Y> AnyPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float + Ease,
+{
+    /// Constructor for an AnyPoint.
+    #[inline]
+    pub fn new(x: X, y: Y, shape: SegmentShape<Y::Scalar>) -> AnyPoint<X, Y> {
+        AnyPoint {
+            x,
+            y,
+            shape,
+        }
+    }
+}
+
+
+impl<X, Y> Point for AnyPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float + Ease,
+{
+    type X = X;
+    type Y = Y;
+
+    #[inline(always)]
+    fn x_to_scalar(x: X) -> Y::Scalar {
+        NumCast::from(x).unwrap()
+    }
+    #[inline(always)]
+    fn x(&self) -> X { self.x }
+    #[inline(always)]
+    fn y(&self) -> Y { self.y }
+    #[inline]
+    fn interpolate(x: X, start: &Self, end: &Self) -> Y {
+        match start.shape {
+            SegmentShape::Linear => interpolate::linear(x, start, end),
+            SegmentShape::Step(jump) => interpolate::step(x, start, end, jump),
+            SegmentShape::Ease(ease_fn) => interpolate::ease(x, start, end, ease_fn),
+            SegmentShape::Bezier(curve) => interpolate::bezier(x, start, end, curve),
+            SegmentShape::Exponential(k) => interpolate::exponential(x, start, end, k),
+            SegmentShape::Cosine => interpolate::cosine(x, start, end),
+        }
+    }
+    #[inline(always)]
+    fn segment_shape(start: &Self, _end: &Self) -> Option<SegmentShape<Y::Scalar>> {
+        Some(start.shape)
+    }
+}
+
+
+impl<X, Y> PointMut for AnyPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float + Ease,
+{
+    #[inline(always)]
+    fn set_x(&mut self, x: X) { self.x = x; }
+    #[inline(always)]
+    fn set_y(&mut self, y: Y) { self.y = y; }
+}
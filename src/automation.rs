@@ -0,0 +1,240 @@
+use envelope::Envelope;
+use envelope_mut::EnvelopeMut;
+use fit::{self, FitPoint};
+use interpolation::Spatial;
+use num::{Float, NumCast};
+use std::mem;
+
+
+/// The most samples that may be thinned into a single segment.
+///
+/// This bounds the cost of thinning each new sample while a control is held still.
+const MAX_SPAN: usize = 1024;
+
+
+/// The way in which an `AutomationRecorder` merges control values into an existing envelope.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AutomationMode {
+    /// Every value is recorded, overwriting the existing curve from the first value until the
+    /// recorder is stopped.
+    Write,
+    /// Values are recorded only while the control is touched. Once released, the curve returns to
+    /// the existing curve.
+    Touch,
+    /// Values are recorded from the first touch until the recorder is stopped, with the last value
+    /// held after the control is released.
+    Latch,
+}
+
+
+/// Records timestamped control values and merges them into an existing envelope.
+///
+/// Values are thinned as they arrive, keeping only the points needed to stay within `tolerance`
+/// of every recorded value, with each point shaped via `FitPoint`. Points whose shape depends on
+/// the samples that follow them (e.g. the tangent of a `HermitePoint`) are only finished once
+/// those samples arrive, so may stray slightly beyond `tolerance`. When recording punches out,
+/// the points of the existing envelope within the recorded range are replaced by the recorded
+/// points, which are joined to the existing curve on either side.
+#[derive(Clone)]
+pub struct AutomationRecorder<P>
+    where P: FitPoint,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    mode: AutomationMode,
+    tolerance: <P::Y as Spatial>::Scalar,
+    is_touched: bool,
+    maybe_pass: Option<Pass<P>>,
+}
+
+/// The points recorded since punching in.
+#[derive(Clone)]
+struct Pass<P>
+    where P: FitPoint,
+{
+    punch_in: P::X,
+    points: Vec<P>,
+    /// The samples of the last span that was thinned into a segment.
+    before: Vec<(P::X, P::Y)>,
+    /// The samples since the last point that was kept.
+    span: Vec<(P::X, P::Y)>,
+}
+
+
+impl<P> AutomationRecorder<P>
+    where P: FitPoint,
+          P::X: PartialOrd,
+          P::Y: NumCast + Clone,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    /// Constructor for an AutomationRecorder that keeps the recorded curve within `tolerance` of
+    /// every recorded value.
+    #[inline]
+    pub fn new(mode: AutomationMode, tolerance: <P::Y as Spatial>::Scalar) -> Self {
+        AutomationRecorder {
+            mode,
+            tolerance,
+            is_touched: false,
+            maybe_pass: None,
+        }
+    }
+
+    /// The mode in which values are merged.
+    #[inline]
+    pub fn mode(&self) -> AutomationMode {
+        self.mode
+    }
+
+    /// Whether or not the control is currently touched.
+    #[inline]
+    pub fn is_touched(&self) -> bool {
+        self.is_touched
+    }
+
+    /// Whether or not values are currently being recorded, i.e. recording has punched in.
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.maybe_pass.is_some()
+    }
+
+    /// The control has been touched, so that values are recorded in `Touch` and `Latch` mode.
+    #[inline]
+    pub fn touch(&mut self) {
+        self.is_touched = true;
+    }
+
+    /// Record the control's value `y` at `x`.
+    ///
+    /// Values are ignored unless the mode and the state of the control call for them to be
+    /// recorded. The first recorded value punches in. Once a control is released in `Latch` mode,
+    /// its last touched value is recorded in place of `y` until the recorder is stopped.
+    ///
+    /// Note: Values must be recorded in order of `x`.
+    pub fn record(&mut self, x: P::X, y: P::Y) {
+        let y = match self.mode {
+            AutomationMode::Write => y,
+            AutomationMode::Touch | AutomationMode::Latch if self.is_touched => y,
+            AutomationMode::Touch => return,
+            AutomationMode::Latch => match self.maybe_pass {
+                Some(ref pass) => pass.last().1.clone(),
+                None => return,
+            },
+        };
+        let tolerance = self.tolerance;
+        self.maybe_pass
+            .get_or_insert_with(|| Pass {
+                punch_in: x.clone(),
+                points: Vec::new(),
+                before: Vec::new(),
+                span: Vec::new(),
+            })
+            .push(x, y, tolerance);
+    }
+
+    /// The control has been released at `x`.
+    ///
+    /// In `Touch` mode, recording punches out and the curve returns to the existing curve of
+    /// `env` by `x`. In the other modes, recording continues until the recorder is stopped.
+    pub fn release<E>(&mut self, env: &mut E, x: P::X)
+        where E: EnvelopeMut<X=P::X, Y=P::Y, Point=P>,
+              E: for<'e> Envelope<'e, X=P::X, Y=P::Y, Point=P>,
+    {
+        self.is_touched = false;
+        if self.mode == AutomationMode::Touch {
+            self.punch_out(env, x);
+        }
+    }
+
+    /// Stop recording at `x`, merging anything that was recorded into `env`.
+    ///
+    /// In `Write` and `Latch` mode the last value is held until `x`, after which the curve jumps
+    /// back to the existing curve. In `Touch` mode, this is the same as releasing the control.
+    pub fn stop<E>(&mut self, env: &mut E, x: P::X)
+        where E: EnvelopeMut<X=P::X, Y=P::Y, Point=P>,
+              E: for<'e> Envelope<'e, X=P::X, Y=P::Y, Point=P>,
+    {
+        self.is_touched = false;
+        self.punch_out(env, x);
+    }
+
+    /// Finish the current pass at `x`, replacing the points of `env` within the recorded range.
+    fn punch_out<E>(&mut self, env: &mut E, x: P::X)
+        where E: EnvelopeMut<X=P::X, Y=P::Y, Point=P>,
+              E: for<'e> Envelope<'e, X=P::X, Y=P::Y, Point=P>,
+    {
+        let mut pass = match self.maybe_pass.take() {
+            Some(pass) => pass,
+            None => return,
+        };
+        let maybe_y_in = env.y(pass.punch_in.clone());
+        let maybe_y_out = env.y(x.clone());
+
+        // Hold the last value in `Write` and `Latch` mode, or glide back to the existing curve in
+        // `Touch` mode.
+        if self.mode != AutomationMode::Touch {
+            let (last_x, last_y) = pass.last().clone();
+            if x > last_x {
+                pass.push(x.clone(), last_y, self.tolerance);
+            }
+        }
+        if let Some(y_out) = maybe_y_out {
+            if *pass.last() != (x.clone(), y_out.clone()) {
+                pass.push(x.clone(), y_out, self.tolerance);
+            }
+        }
+        let punch_out = pass.last().0.clone();
+        let points = pass.finish();
+
+        // Replace the points within the punched in range.
+        let punch_in = pass.punch_in;
+        env.retain(|p| p.x() < punch_in || p.x() > punch_out);
+        if let Some(y_in) = maybe_y_in {
+            if points.first().is_none_or(|p| p.y() != y_in) {
+                env.insert_point(P::fit(&[], (punch_in, y_in), &[]));
+            }
+        }
+        for point in points {
+            env.insert_point(point);
+        }
+    }
+}
+
+
+impl<P> Pass<P>
+    where P: FitPoint,
+          P::Y: NumCast + Clone,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    /// The latest sample.
+    fn last(&self) -> &(P::X, P::Y) {
+        self.span.last().expect("a pass always begins with a sample")
+    }
+
+    /// Add a sample, keeping the previous sample as a point if the span can no longer be fitted
+    /// by a single segment.
+    fn push(&mut self, x: P::X, y: P::Y, tolerance: <P::Y as Spatial>::Scalar) {
+        self.span.push((x, y));
+        let len = self.span.len();
+        if len <= 2 {
+            return;
+        }
+        if len <= MAX_SPAN && fit::span_error::<P>(&self.before, &self.span) <= tolerance {
+            return;
+        }
+        let sample = self.span.pop().unwrap();
+        let end = self.span.last().unwrap().clone();
+        self.points.push(P::fit(&self.before, self.span[0].clone(), &self.span[1..]));
+        self.span.pop();
+        self.before = mem::replace(&mut self.span, vec![end, sample]);
+    }
+
+    /// Fit the points of the remaining span.
+    fn finish(&mut self) -> Vec<P> {
+        let mut points = mem::take(&mut self.points);
+        let last = self.span.len() - 1;
+        points.push(P::fit(&self.before, self.span[0].clone(), &self.span[1..]));
+        if last > 0 {
+            points.push(P::fit(&self.span[..last], self.span[last].clone(), &[]));
+        }
+        points
+    }
+}
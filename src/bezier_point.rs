@@ -1,7 +1,8 @@
 use interpolate;
 use interpolation::Spatial;
 use num::{Float, NumCast};
-use point::Point;
+use point::{Point, PointMut};
+use shape::SegmentShape;
 
 
 /// A type whose interpolation may involve some quadratic bezier curve.
@@ -26,9 +27,9 @@ impl<X, Y> BezierPoint<X, Y> where
     #[inline]
     pub fn new(x: X, y: Y, curve: Y::Scalar) -> BezierPoint<X, Y> {
         BezierPoint {
-            x: x,
-            y: y,
-            curve: curve,
+            x,
+            y,
+            curve,
         }
     }
 }
@@ -57,5 +58,20 @@ impl<X, Y> Point for BezierPoint<X, Y>
     {
         interpolate::bezier(x, start, end, start.curve)
     }
+    #[inline(always)]
+    fn segment_shape(start: &Self, _end: &Self) -> Option<SegmentShape<Y::Scalar>> {
+        Some(SegmentShape::Bezier(start.curve))
+    }
 }
 
+
+impl<X, Y> PointMut for BezierPoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    #[inline(always)]
+    fn set_x(&mut self, x: X) { self.x = x; }
+    #[inline(always)]
+    fn set_y(&mut self, y: Y) { self.y = y; }
+}
@@ -0,0 +1,586 @@
+use envelope::Envelope;
+use envelope_mut::EnvelopeMut;
+use point::{Breakpoint, PointMut};
+use std;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+
+
+/// The error returned when an envelope is constructed from points that are not sorted by `x`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnsortedError {
+    /// The index of the first point whose `x` comes before that of the preceding point.
+    pub idx: usize,
+}
+
+/// The error returned when more points are given to an `ArrayEnvelope` than it can hold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CapacityError {
+    /// The fixed capacity of the `ArrayEnvelope`.
+    pub capacity: usize,
+}
+
+
+/// The error returned when an `ArrayEnvelope` is constructed from points that are either not
+/// sorted by `x` or too many for it to hold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArrayEnvelopeError {
+    /// The points are not sorted by `x`.
+    Unsorted(UnsortedError),
+    /// There are more points than the `ArrayEnvelope` can hold.
+    Capacity(CapacityError),
+}
+
+
+/// An owned, growable `Envelope` whose points are always sorted by `x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VecEnvelope<P> {
+    points: Vec<P>,
+}
+
+/// An `Envelope` over a borrowed slice of points that are sorted by `x`.
+#[derive(Debug, PartialEq)]
+pub struct SliceEnvelope<'a, P: 'a> {
+    points: &'a [P],
+}
+
+/// An `Envelope` that stores up to `N` points inline, sorted by `x`.
+///
+/// No heap allocation is required, making this suitable for use on the audio thread.
+#[derive(Clone)]
+pub struct ArrayEnvelope<P, const N: usize> {
+    /// `None` until the first point is added. Slots beyond `len` hold copies of previously added
+    /// points, which allows the container to be implemented without uninitialised memory.
+    points: Option<[P; N]>,
+    len: usize,
+}
+
+
+/// A total order over `x`s in which any `x` that cannot be compared with itself (i.e. NaN) comes
+/// after every other `x`.
+pub(crate) fn cmp_x<X>(a: &X, b: &X) -> std::cmp::Ordering
+    where X: PartialOrd,
+{
+    let is_nan = |x: &X| x.partial_cmp(x).is_none();
+    match (is_nan(a), is_nan(b)) {
+        (false, false) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (false, true) => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (true, true) => Ordering::Equal,
+    }
+}
+
+/// Check that the given points are sorted by `x`, with any NaN `x`s last.
+fn check_sorted<P>(points: &[P]) -> Result<(), UnsortedError>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    let is_unsorted = |pair: &[P]| cmp_x(&pair[1].x(), &pair[0].x()) == Ordering::Less;
+    match points.windows(2).position(is_unsorted) {
+        Some(i) => Err(UnsortedError { idx: i + 1 }),
+        None => Ok(()),
+    }
+}
+
+/// Stable sort the given points by `x` so that points sharing an `x` retain their order.
+///
+/// Points with a NaN `x` are placed last.
+fn sort_points<P>(points: &mut [P])
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    points.sort_by(|a, b| cmp_x(&a.x(), &b.x()));
+}
+
+
+impl<P> VecEnvelope<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Construct an empty `VecEnvelope`.
+    #[inline]
+    pub fn new() -> Self {
+        VecEnvelope { points: Vec::new() }
+    }
+
+    /// Construct a `VecEnvelope` from points that are already sorted by `x`.
+    ///
+    /// Returns an `UnsortedError` if the points are out of order.
+    #[inline]
+    pub fn from_sorted(points: Vec<P>) -> Result<Self, UnsortedError> {
+        check_sorted(&points)?;
+        Ok(VecEnvelope { points })
+    }
+
+    /// The sorted points as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[P] {
+        &self.points
+    }
+
+    /// Consume the `VecEnvelope` and return the sorted points.
+    #[inline]
+    pub fn into_vec(self) -> Vec<P> {
+        self.points
+    }
+}
+
+impl<'a, P> SliceEnvelope<'a, P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Construct a `SliceEnvelope` from points that are already sorted by `x`.
+    ///
+    /// Returns an `UnsortedError` if the points are out of order. As the points are borrowed
+    /// they cannot be sorted in place, so there are no sorting constructors for this type.
+    #[inline]
+    pub fn from_sorted(points: &'a [P]) -> Result<Self, UnsortedError> {
+        check_sorted(points)?;
+        Ok(SliceEnvelope { points })
+    }
+
+    /// The sorted points as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &'a [P] {
+        self.points
+    }
+}
+
+impl<P, const N: usize> ArrayEnvelope<P, N>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Construct an empty `ArrayEnvelope`.
+    #[inline]
+    pub fn new() -> Self {
+        ArrayEnvelope { points: None, len: 0 }
+    }
+
+    /// Construct an `ArrayEnvelope` from points that are already sorted by `x`.
+    ///
+    /// Returns an error if there are more than `N` points or if the points are out of order.
+    pub fn from_sorted(points: Vec<P>) -> Result<Self, ArrayEnvelopeError> {
+        if points.len() > N {
+            return Err(CapacityError { capacity: N }.into());
+        }
+        check_sorted(&points)?;
+        let mut env = Self::new();
+        for point in points {
+            env.push(point)?;
+        }
+        Ok(env)
+    }
+
+    /// The maximum number of points that the `ArrayEnvelope` can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of points in the `ArrayEnvelope`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether or not the `ArrayEnvelope` contains any points.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether or not the `ArrayEnvelope` has reached its capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// The sorted points as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[P] {
+        match self.points {
+            Some(ref points) => &points[..self.len],
+            None => &[],
+        }
+    }
+
+    /// Insert the given point at its sorted position, returning the index at which it was
+    /// inserted.
+    ///
+    /// Returns a `CapacityError` if the `ArrayEnvelope` is already full.
+    pub fn try_insert_point(&mut self, point: P) -> Result<usize, CapacityError> {
+        let len = self.len;
+        let x = point.x();
+        let idx = self.as_slice().partition_point(|p| cmp_x(&p.x(), &x) != Ordering::Greater);
+        self.push(point)?;
+        if let Some(ref mut points) = self.points {
+            points[idx..len + 1].rotate_right(1);
+        }
+        Ok(idx)
+    }
+
+    /// Write the given point to the end of the array without regard for ordering.
+    fn push(&mut self, point: P) -> Result<(), CapacityError> {
+        if self.len == N {
+            return Err(CapacityError { capacity: N });
+        }
+        match self.points {
+            Some(ref mut points) => points[self.len] = point,
+            None => self.points = Some(std::array::from_fn(|_| point.clone())),
+        }
+        self.len += 1;
+        Ok(())
+    }
+}
+
+
+impl<P> Default for VecEnvelope<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    #[inline]
+    fn default() -> Self {
+        VecEnvelope::new()
+    }
+}
+
+impl<P, const N: usize> Default for ArrayEnvelope<P, N>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    #[inline]
+    fn default() -> Self {
+        ArrayEnvelope::new()
+    }
+}
+
+impl<P, const N: usize> std::fmt::Debug for ArrayEnvelope<P, N>
+    where P: Breakpoint + std::fmt::Debug,
+          P::X: PartialOrd,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<P, const N: usize> PartialEq for ArrayEnvelope<P, N>
+    where P: Breakpoint + PartialEq,
+          P::X: PartialOrd,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<'a, P> Clone for SliceEnvelope<'a, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, P> Copy for SliceEnvelope<'a, P> {}
+
+
+impl<P> From<Vec<P>> for VecEnvelope<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Sorts the given points by `x`.
+    #[inline]
+    fn from(mut points: Vec<P>) -> Self {
+        sort_points(&mut points);
+        VecEnvelope { points }
+    }
+}
+
+impl<P, const N: usize> TryFrom<Vec<P>> for ArrayEnvelope<P, N>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    type Error = CapacityError;
+    /// Sorts the given points by `x`.
+    ///
+    /// Returns a `CapacityError` if there are more than `N` points.
+    #[inline]
+    fn try_from(mut points: Vec<P>) -> Result<Self, CapacityError> {
+        if points.len() > N {
+            return Err(CapacityError { capacity: N });
+        }
+        sort_points(&mut points);
+        let mut env = ArrayEnvelope::new();
+        for point in points {
+            env.push(point)?;
+        }
+        Ok(env)
+    }
+}
+
+impl<'a, P> TryFrom<&'a [P]> for SliceEnvelope<'a, P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    type Error = UnsortedError;
+    #[inline]
+    fn try_from(points: &'a [P]) -> Result<Self, UnsortedError> {
+        SliceEnvelope::from_sorted(points)
+    }
+}
+
+impl<'a, P> TryFrom<&'a Vec<P>> for SliceEnvelope<'a, P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    type Error = UnsortedError;
+    #[inline]
+    fn try_from(points: &'a Vec<P>) -> Result<Self, UnsortedError> {
+        SliceEnvelope::from_sorted(points)
+    }
+}
+
+impl<P> FromIterator<P> for VecEnvelope<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Collects and sorts the points by `x`.
+    #[inline]
+    fn from_iter<I: IntoIterator<Item=P>>(points: I) -> Self {
+        VecEnvelope::from(points.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<P, const N: usize> FromIterator<P> for ArrayEnvelope<P, N>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Collects and sorts the points by `x`.
+    ///
+    /// **Panics** if the iterator yields more than `N` points. Use `ArrayEnvelope::try_from` to
+    /// handle this case instead.
+    #[inline]
+    fn from_iter<I: IntoIterator<Item=P>>(points: I) -> Self {
+        let mut env = ArrayEnvelope::new();
+        env.extend(points);
+        env
+    }
+}
+
+impl<P> Extend<P> for VecEnvelope<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Adds the points, placing each after any existing points that share its `x`.
+    #[inline]
+    fn extend<I: IntoIterator<Item=P>>(&mut self, points: I) {
+        self.points.extend(points);
+        sort_points(&mut self.points);
+    }
+}
+
+impl<P, const N: usize> Extend<P> for ArrayEnvelope<P, N>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    /// Adds the points, placing each after any existing points that share its `x`.
+    ///
+    /// **Panics** if the total number of points would exceed `N`. Use
+    /// `ArrayEnvelope::try_insert_point` to handle this case instead.
+    #[inline]
+    fn extend<I: IntoIterator<Item=P>>(&mut self, points: I) {
+        for point in points {
+            self.insert_point(point);
+        }
+    }
+}
+
+
+impl<'a, P> Envelope<'a> for VecEnvelope<P>
+    where P: Breakpoint + 'a,
+          P::X: PartialOrd,
+{
+    type X = P::X;
+    type Y = P::Y;
+    type Point = P;
+    type Points = std::slice::Iter<'a, P>;
+    #[inline]
+    fn points(&'a self) -> Self::Points {
+        self.points.iter()
+    }
+    #[inline]
+    fn points_slice(&'a self) -> Option<&'a [P]> {
+        Some(&self.points)
+    }
+}
+
+impl<'a, 'b, P> Envelope<'b> for SliceEnvelope<'a, P>
+    where 'a: 'b,
+          P: Breakpoint + 'b,
+          P::X: PartialOrd,
+{
+    type X = P::X;
+    type Y = P::Y;
+    type Point = P;
+    type Points = std::slice::Iter<'b, P>;
+    #[inline]
+    fn points(&'b self) -> Self::Points {
+        self.points.iter()
+    }
+    #[inline]
+    fn points_slice(&'b self) -> Option<&'b [P]> {
+        Some(self.points)
+    }
+}
+
+impl<'a, P, const N: usize> Envelope<'a> for ArrayEnvelope<P, N>
+    where P: Breakpoint + 'a,
+          P::X: PartialOrd,
+{
+    type X = P::X;
+    type Y = P::Y;
+    type Point = P;
+    type Points = std::slice::Iter<'a, P>;
+    #[inline]
+    fn points(&'a self) -> Self::Points {
+        self.as_slice().iter()
+    }
+    #[inline]
+    fn points_slice(&'a self) -> Option<&'a [P]> {
+        Some(self.as_slice())
+    }
+}
+
+
+impl<P> EnvelopeMut for VecEnvelope<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    type X = P::X;
+    type Y = P::Y;
+    type Point = P;
+
+    #[inline]
+    fn insert_point(&mut self, point: P) -> usize {
+        self.points.insert_point(point)
+    }
+
+    #[inline]
+    fn remove_point(&mut self, idx: usize) -> Option<P> {
+        self.points.remove_point(idx)
+    }
+
+    #[inline]
+    fn move_point(&mut self, idx: usize, x: P::X, y: P::Y) -> Option<usize>
+        where P: PointMut,
+    {
+        self.points.move_point(idx, x, y)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, f: F)
+        where F: FnMut(&P) -> bool,
+    {
+        self.points.retain(f)
+    }
+}
+
+impl<P, const N: usize> EnvelopeMut for ArrayEnvelope<P, N>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    type X = P::X;
+    type Y = P::Y;
+    type Point = P;
+
+    /// **Panics** if the `ArrayEnvelope` is already full. See `try_insert_point` for a
+    /// non-panicking alternative.
+    #[inline]
+    fn insert_point(&mut self, point: P) -> usize {
+        self.try_insert_point(point).expect("ArrayEnvelope is full")
+    }
+
+    fn remove_point(&mut self, idx: usize) -> Option<P> {
+        if idx >= self.len {
+            return None;
+        }
+        let len = self.len;
+        self.len -= 1;
+        self.points.as_mut().map(|points| {
+            points[idx..len].rotate_left(1);
+            points[len - 1].clone()
+        })
+    }
+
+    #[inline]
+    fn move_point(&mut self, idx: usize, x: P::X, y: P::Y) -> Option<usize>
+        where P: PointMut,
+    {
+        if self.as_slice().get(idx)?.x() == x {
+            self.points.as_mut()?[idx].set_y(y);
+            return Some(idx);
+        }
+        self.remove_point(idx).map(|mut point| {
+            point.set_x(x);
+            point.set_y(y);
+            self.insert_point(point)
+        })
+    }
+
+    fn retain<F>(&mut self, mut f: F)
+        where F: FnMut(&P) -> bool,
+    {
+        let len = self.len;
+        if let Some(ref mut points) = self.points {
+            let mut kept = 0;
+            for i in 0..len {
+                if f(&points[i]) {
+                    points.swap(kept, i);
+                    kept += 1;
+                }
+            }
+            self.len = kept;
+        }
+    }
+}
+
+
+impl std::fmt::Display for UnsortedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the point at index {} comes before its predecessor on the x axis", self.idx)
+    }
+}
+
+impl std::fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the envelope cannot hold more than {} points", self.capacity)
+    }
+}
+
+impl std::fmt::Display for ArrayEnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ArrayEnvelopeError::Unsorted(ref err) => err.fmt(f),
+            ArrayEnvelopeError::Capacity(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for UnsortedError {}
+
+impl std::error::Error for CapacityError {}
+
+impl std::error::Error for ArrayEnvelopeError {}
+
+impl From<UnsortedError> for ArrayEnvelopeError {
+    #[inline]
+    fn from(err: UnsortedError) -> Self {
+        ArrayEnvelopeError::Unsorted(err)
+    }
+}
+
+impl From<CapacityError> for ArrayEnvelopeError {
+    #[inline]
+    fn from(err: CapacityError) -> Self {
+        ArrayEnvelopeError::Capacity(err)
+    }
+}
@@ -0,0 +1,80 @@
+use interpolate;
+use interpolation::Spatial;
+use num::{Float, NumCast};
+use point::{Point, PointMut};
+
+
+/// A type whose interpolation follows a cubic bezier curve shaped by a pair of handles.
+///
+/// The curve between two points is shaped by the `out_handle` of the first point and the
+/// `in_handle` of the second, as in most graphics and animation editors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicBezierPoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    pub x: X,
+    pub y: Y,
+    /// The offset of the handle shaping the curve that arrives at this point, in `(x, y)`.
+    pub in_handle: (Y::Scalar, Y::Scalar),
+    /// The offset of the handle shaping the curve that leaves this point, in `(x, y)`.
+    pub out_handle: (Y::Scalar, Y::Scalar),
+}
+
+
+impl<X, Y> CubicBezierPoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    /// Constructor for a CubicBezierPoint.
+    #[inline]
+    pub fn new(x: X,
+               y: Y,
+               in_handle: (Y::Scalar, Y::Scalar),
+               out_handle: (Y::Scalar, Y::Scalar)) -> CubicBezierPoint<X, Y>
+    {
+        CubicBezierPoint {
+            x,
+            y,
+            in_handle,
+            out_handle,
+        }
+    }
+}
+
+
+impl<X, Y> Point for CubicBezierPoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    type X = X;
+    type Y = Y;
+
+    #[inline(always)]
+    fn x_to_scalar(x: X) -> Y::Scalar {
+        NumCast::from(x).unwrap()
+    }
+    #[inline(always)]
+    fn x(&self) -> X { self.x }
+    #[inline(always)]
+    fn y(&self) -> Y { self.y }
+    #[inline(always)]
+    fn interpolate(x: X, start: &Self, end: &Self) -> Y {
+        interpolate::cubic_bezier(x, start, end, start.out_handle, end.in_handle)
+    }
+}
+
+
+impl<X, Y> PointMut for CubicBezierPoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    #[inline(always)]
+    fn set_x(&mut self, x: X) { self.x = x; }
+    #[inline(always)]
+    fn set_y(&mut self, y: Y) { self.y = y; }
+}
@@ -0,0 +1,207 @@
+use envelope::Envelope;
+use interpolation::{Ease, Spatial};
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
+use shape::SegmentShape;
+
+
+/// The slope of the envelope `E`, exposed as a curve that may be evaluated at any `x`.
+///
+/// The slope may be sampled once per `step` via `steps`. As it has no points of its own,
+/// `Derivative` is not itself an `Envelope`, so point-based queries such as `x_at_y` or
+/// `y_range` are not available for it.
+///
+/// See `Envelope::dy_dx` for details.
+#[derive(Copy, Clone, Debug)]
+pub struct Derivative<'a, E>
+    where E: 'a,
+{
+    env: &'a E,
+}
+
+/// An iterator that samples the slope of the envelope `E` once per `step`.
+///
+/// Yields `None` once the steps fall out of range of the envelope's points.
+#[derive(Clone, Debug)]
+pub struct DerivativeSteps<'a, E>
+    where E: 'a,
+{
+    derivative: Derivative<'a, E>,
+    start: f64,
+    step: f64,
+    n: u64,
+}
+
+
+impl<'a, E> Derivative<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    /// The slope of the given envelope.
+    #[inline]
+    pub fn new(env: &'a E) -> Self {
+        Derivative { env }
+    }
+
+    /// The envelope whose slope is described.
+    #[inline]
+    pub fn envelope(&self) -> &'a E {
+        self.env
+    }
+
+    /// The slope of the envelope at the given `x`.
+    #[inline]
+    pub fn y(&self, x: E::X) -> Option<<E::Y as Spatial>::Scalar> {
+        dy_dx(self.env, x)
+    }
+
+    /// Sample the slope once per `step` starting from `start`.
+    ///
+    /// As with `Envelope::sample_steps`, the `x` of each step is calculated directly from its
+    /// index rather than accumulated.
+    #[inline]
+    pub fn steps(&self, start: E::X, step: E::X) -> DerivativeSteps<'a, E> {
+        DerivativeSteps {
+            derivative: Derivative { env: self.env },
+            start: scalar(start),
+            step: scalar(step),
+            n: 0,
+        }
+    }
+}
+
+impl<'a, E> DerivativeSteps<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    /// Fill `out` with the following steps.
+    ///
+    /// Returns the number of values written, which is less than `out.len()` if the steps fell
+    /// out of range. Any remaining values in `out` are left untouched.
+    pub fn fill(&mut self, out: &mut [<E::Y as Spatial>::Scalar]) -> usize {
+        for (written, slope) in out.iter_mut().enumerate() {
+            match self.next() {
+                Some(next) => *slope = next,
+                None => return written,
+            }
+        }
+        out.len()
+    }
+}
+
+impl<'a, E> Iterator for DerivativeSteps<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    type Item = <E::Y as Spatial>::Scalar;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = scalar(self.start + self.step * self.n as f64);
+        self.n += 1;
+        self.derivative.y(x)
+    }
+}
+
+
+/// The slope of the envelope at the given `x`.
+///
+/// Where `x` lies on a point, the slope of the segment beginning at that point is used, unless it
+/// is the last point.
+pub fn dy_dx<'a, E>(env: &'a E, x: E::X) -> Option<<E::Y as Spatial>::Scalar>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    // With random access to the points we can find the segment via binary search.
+    if let Some(points) = env.points_slice() {
+        let idx = match points.partition_point(|point| Breakpoint::x(point) <= x) {
+            0 => return None,
+            i => i - 1,
+        };
+        let left_idx = if idx + 1 < points.len() {
+            idx
+        } else {
+            // `x` lands on the last point, so use the last segment that isn't vertical.
+            if x != Breakpoint::x(&points[idx]) {
+                return None;
+            }
+            let is_sloped = |&i: &usize| Breakpoint::x(&points[i - 1]) < Breakpoint::x(&points[i]);
+            match (1..points.len()).rev().find(is_sloped) {
+                Some(i) => i - 1,
+                None => return Some(scalar(0.0)),
+            }
+        };
+        let maybe_prev = left_idx.checked_sub(1).map(|i| &points[i]);
+        let (left, right) = (&points[left_idx], &points[left_idx + 1]);
+        return Some(segment_dy_dx(x, maybe_prev, left, right, points.get(left_idx + 2)));
+    }
+
+    let mut points = env.points();
+    let mut left = points.next()?;
+    if x < Breakpoint::x(left) {
+        return None;
+    }
+
+    // Find the segment in which `x` lies, remembering the last segment that isn't vertical in
+    // case `x` lands on the last point.
+    let mut maybe_prev = None;
+    let mut maybe_sloped = None;
+    while let Some(right) = points.next() {
+        let maybe_next = points.clone().next();
+        if x < Breakpoint::x(right) {
+            return Some(segment_dy_dx(x, maybe_prev, left, right, maybe_next));
+        }
+        if Breakpoint::x(left) < Breakpoint::x(right) {
+            maybe_sloped = Some((maybe_prev, left, right, maybe_next));
+        }
+        maybe_prev = Some(left);
+        left = right;
+    }
+
+    if x != Breakpoint::x(left) {
+        return None;
+    }
+    match maybe_sloped {
+        Some((maybe_prev, left, right, maybe_next)) =>
+            Some(segment_dy_dx(x, maybe_prev, left, right, maybe_next)),
+        None => Some(scalar(0.0)),
+    }
+}
+
+/// The slope of the segment from `left` to `right` at the given `x`.
+fn segment_dy_dx<P>(x: P::X, maybe_prev: Option<&P>, left: &P, right: &P, maybe_next: Option<&P>)
+    -> <P::Y as Spatial>::Scalar
+    where P: Point,
+          P::X: NumCast,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float + Ease,
+{
+    let start = (P::x_to_scalar(Breakpoint::x(left)), scalar(Breakpoint::y(left)));
+    let end = (P::x_to_scalar(Breakpoint::x(right)), scalar(Breakpoint::y(right)));
+    let x = P::x_to_scalar(x);
+    match Point::segment_shape(left, right) {
+        Some(shape) => shape.dy_dx(start, end, x),
+        // Without a known shape, even a segment between equal `y`s may bend between them.
+        None => if start.0 == end.0 {
+            SegmentShape::Linear.dy_dx(start, end, x)
+        } else {
+            let f = |x| -> <P::Y as Spatial>::Scalar {
+                scalar(Breakpoint::interpolate_segment(scalar(x), maybe_prev, left, right,
+                                                       maybe_next))
+            };
+            numeric::derivative(f, x, start.0, end.0)
+        },
+    }
+}
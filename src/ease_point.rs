@@ -1,6 +1,7 @@
 use interpolate;
 use interpolation::{Ease, EaseFunction, Spatial};
-use point::Point;
+use point::{Point, PointMut};
+use shape::SegmentShape;
 use num::{Float, NumCast};
 
 
@@ -26,9 +27,9 @@ impl<X, Y> EasePoint<X, Y>
     #[inline]
     pub fn new(x: X, y: Y, maybe_ease_fn: Option<EaseFunction>) -> EasePoint<X, Y> {
         EasePoint {
-            x: x,
-            y: y,
-            maybe_ease_fn: maybe_ease_fn,
+            x,
+            y,
+            maybe_ease_fn,
         }
     }
 }
@@ -57,5 +58,23 @@ impl<X, Y> Point for EasePoint<X, Y>
             None => interpolate::linear(x, start, end),
         }
     }
+    #[inline(always)]
+    fn segment_shape(start: &Self, _end: &Self) -> Option<SegmentShape<Y::Scalar>> {
+        match start.maybe_ease_fn {
+            Some(ease_fn) => Some(SegmentShape::Ease(ease_fn)),
+            None => Some(SegmentShape::Linear),
+        }
+    }
 }
 
+
+impl<X, Y> PointMut for EasePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + Spatial + Clone + Copy,
+          Y::Scalar: Float + Ease,
+{
+    #[inline(always)]
+    fn set_x(&mut self, x: X) { self.x = x; }
+    #[inline(always)]
+    fn set_y(&mut self, y: Y) { self.y = y; }
+}
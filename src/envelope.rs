@@ -1,16 +1,27 @@
 
-use interpolate::Scalar;
-use interpolation::Spatial;
-use point::Point;
+use derivative::{self, Derivative};
+use extrapolation::{self, Extrapolated, Extrapolation};
+use integral::{self, RunningIntegral};
+use inverse::{self, Inverse, InverseError};
+use interpolation::{Ease, Spatial};
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
+use range::{self, Bounds, RangeIndex};
+use shape::SegmentShape;
+use simplify;
 use std;
+use std::collections::VecDeque;
+use std::ops::{Add, Rem, Sub};
+use time::{SampleClock, Time};
 
 
 /// Types that are representable as an Envelope.
 pub trait Envelope<'a>: Sized {
     type X: PartialEq + PartialOrd + Clone;
-    type Y: PartialEq + Spatial;
+    type Y: PartialEq;
     /// The `Point` type which may be referenced and interpolated by the `Envelope`.
-    type Point: Point<X=Self::X, Y=Self::Y> + 'a;
+    type Point: Breakpoint<X=Self::X, Y=Self::Y> + 'a;
     /// An iterator yielding references to `Self::Point`s.
     type Points: Iterator<Item=&'a Self::Point>
         + ExactSizeIterator
@@ -21,6 +32,15 @@ pub trait Envelope<'a>: Sized {
     /// An iterator yielding the `Point`s of the Envelope.
     fn points(&'a self) -> Self::Points;
 
+    /// The `Point`s of the Envelope as a contiguous slice, if they are stored as one.
+    ///
+    /// Envelopes that override this allow all index and neighbour queries to use a binary search
+    /// rather than a linear scan over `points`. By default `None` is returned.
+    #[inline]
+    fn points_slice(&'a self) -> Option<&'a [Self::Point]> {
+        None
+    }
+
     /// The index of the `Point` that comes directly before the given `x`.
     #[inline]
     fn point_idx_before(&'a self, x: Self::X) -> Option<usize> {
@@ -98,19 +118,23 @@ pub trait Envelope<'a>: Sized {
     /// A reference to the first point lying directly on the given `x` if there is one.
     #[inline]
     fn point_at(&'a self, x: Self::X) -> Option<&'a Self::Point> {
-        self.points().find(|p| p.x() == x)
+        self.point_at_with_idx(x).map(|(_, p)| p)
     }
 
     /// A reference to the first point (along with it's index) lying directly on the given `x` if
     /// there is one.
     #[inline]
     fn point_at_with_idx(&'a self, x: Self::X) -> Option<(usize, &'a Self::Point)> {
-        self.points().enumerate().find(|&(_, p)| p.x() == x)
+        match self.points_slice() {
+            Some(points) => {
+                let i = points.partition_point(|p| p.x() < x);
+                points.get(i).and_then(|p| if p.x() == x { Some((i, p)) } else { None })
+            },
+            None => self.points().enumerate().find(|&(_, p)| p.x() == x),
+        }
     }
 
     /// The points that lie on either side of the given `x`.
-    ///
-    /// FIXME: This could be much faster.
     #[inline]
     fn surrounding_points(&'a self, x: Self::X)
         -> (Option<&'a Self::Point>, Option<&'a Self::Point>)
@@ -119,8 +143,6 @@ pub trait Envelope<'a>: Sized {
     }
 
     /// A reference point that is closest to the given `x` if there is one.
-    ///
-    /// FIXME: This could be much faster.
     #[inline]
     fn closest_point(&'a self, x: Self::X) -> Option<&'a Self::Point>
         where <Self as Envelope<'a>>::X: std::ops::Sub<Output=<Self as Envelope<'a>>::X>,
@@ -138,70 +160,388 @@ pub trait Envelope<'a>: Sized {
     /// If there is less than two points interpolation is not meaningful,
     /// thus we should just return None.
     ///
+    /// Returns `None` if `x` lies outside the range of the points. See `y_extrapolated` for
+    /// evaluating beyond the first and last points.
+    ///
     /// Note: It is assumed that the points owned by the Envelope are sorted by `x`.
     #[inline]
-    fn y(&'a self, x: Self::X) -> Option<Self::Y>
-        where <Self::Y as Spatial>::Scalar: Scalar,
-    {
+    fn y(&'a self, x: Self::X) -> Option<Self::Y> {
         y(self, x)
     }
 
+    /// Return `y` for the given `x`, using the given `Extrapolation` if `x` lies outside the range
+    /// of the points.
+    ///
+    /// Returns `None` if there are no points, or if `x` is out of range and the extrapolation is
+    /// `Extrapolation::None`.
+    ///
+    /// Note: It is assumed that the points owned by the Envelope are sorted by `x`.
+    #[inline]
+    fn y_extrapolated(&'a self, x: Self::X, extrapolation: Extrapolation) -> Option<Self::Y>
+        where Self::X: Add<Output=Self::X> + Sub<Output=Self::X> + Rem<Output=Self::X>,
+    {
+        extrapolation::y(self, x, extrapolation)
+    }
+
+    /// Wrap the `Envelope` so that `y`, `steps` and `cursor` use the given `Extrapolation` when
+    /// `x` falls outside the range of the points.
+    #[inline]
+    fn extrapolate(self, extrapolation: Extrapolation) -> Extrapolated<Self> {
+        Extrapolated::new(self, extrapolation)
+    }
+
     /// Sample the `Envelope`'s `y` value for every given positive `x` step starting from the first
     /// point's `X` value.
     ///
-    /// The envelope will yield `Some(Y)` until the first step is out of range of all points on the
-    /// y axis.
+    /// The envelope will yield `Some(Y)` until the first step for which `y` returns `None`, i.e.
+    /// the first step that is out of range of all points on the x axis. Envelopes with some
+    /// `Extrapolation` (see `Envelope::extrapolate`) may yield values indefinitely.
     ///
-    /// Returns `None` if `start` is outside the bounds of all points.
+    /// Returns `None` if `y` returns `None` for `start`.
     ///
     /// Note: This method assumes that the envelope points are ordered.
     #[inline]
     fn steps(&'a self, start: Self::X, step: Self::X) -> Option<Steps<'a, Self>> {
         let mut points = self.points();
-        points.next().and_then(|mut left| {
-            let mut maybe_right = None;
-
-            // Iterate through `points` until `start` is between `left` and `right`
-            while let Some(point) = points.next() {
-                maybe_right = Some(point);
-                if point.x() < start {
-                    left = maybe_right.take().unwrap();
-                } else {
-                    break;
+        let (maybe_prev, left, maybe_right) = match self.points_slice() {
+            Some(slice) => {
+                let left_idx = slice.partition_point(|p| p.x() < start).saturating_sub(1);
+                let maybe_prev = left_idx.checked_sub(1).map(|i| &slice[i]);
+                match points.nth(left_idx) {
+                    Some(left) => (maybe_prev, left, points.next()),
+                    None => return None,
                 }
-            }
+            },
+            None => match points.next() {
+                Some(mut left) => {
+                    let mut maybe_prev = None;
+                    let mut maybe_right = None;
 
-            // Check that the remaining points bound the `start`.
-            match maybe_right {
-                Some(right) => if right.x() < start { return None; },
-                None => if left.x() < start { return None; },
-            }
+                    // Iterate through `points` until `start` is between `left` and `right`
+                    for point in points.by_ref() {
+                        maybe_right = Some(point);
+                        if point.x() < start {
+                            maybe_prev = Some(left);
+                            left = maybe_right.take().unwrap();
+                        } else {
+                            break;
+                        }
+                    }
+                    (maybe_prev, left, maybe_right)
+                },
+                None => return None,
+            },
+        };
 
-            Some(Steps {
-                points: points,
-                step: step,
-                next_x: start,
-                left: left,
-                maybe_right: maybe_right,
-                env: std::marker::PhantomData,
-            })
+        // Check that the envelope may be evaluated at `start`.
+        self.y(start.clone())?;
+
+        Some(Steps {
+            points,
+            step,
+            next_x: start,
+            compensation: None,
+            maybe_prev,
+            left,
+            maybe_right,
+            env: self,
         })
     }
 
-    // /// An iterator yielding the X for each point at which the envelope intersects the given `y`.
-    // ///
-    // /// If there are any periods at which X is continuous, only the start X of the continuous
-    // /// period will be returned.
-    // fn xs_at_y(&self, y: Y) -> XsAtY {
-    //     unimplemented!();
-    // }
+    /// Sample the `Envelope`'s `y` value once per sample at the given `sample_rate`, starting from
+    /// the sample at index `start`.
+    ///
+    /// Unlike `steps`, the `x` of each sample is calculated directly from its integer index as
+    /// `index / sample_rate` rather than accumulated, so that the position of each sample remains
+    /// exact no matter how many samples have been taken.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn sample_steps(&'a self, start: u64, sample_rate: f64) -> SampleSteps<'a, Self>
+        where Self::X: NumCast,
+    {
+        SampleSteps {
+            stepper: SampleStepper::new(self, start),
+            sample_rate,
+        }
+    }
+
+    /// Sample the `Envelope`'s `y` value once per sample of the given `SampleClock`, starting from
+    /// the sample at index `start`.
+    ///
+    /// This is the same as `sample_steps`, but for envelopes authored in seconds, milliseconds or
+    /// as a `Duration`, where each sample's `x` is given by the `SampleClock`.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn clock_steps(&'a self, start: u64, clock: SampleClock) -> ClockSteps<'a, Self>
+        where Self::X: Time,
+    {
+        ClockSteps {
+            stepper: SampleStepper::new(self, start),
+            clock,
+        }
+    }
+
+    /// Fill `out` with the `Envelope`'s `y` value for every sample of the given `SampleClock`,
+    /// starting from the sample at index `start_sample`.
+    ///
+    /// This saves converting between samples and the envelope's `X` by hand when the envelope is
+    /// authored in seconds, milliseconds or as a `Duration`.
+    ///
+    /// Returns the number of values written, which is less than `out.len()` if the envelope fell
+    /// out of range. Any remaining values in `out` are left untouched.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn render_samples(&'a self, clock: SampleClock, start_sample: u64, out: &mut [Self::Y])
+        -> usize
+        where Self::X: Time,
+    {
+        self.clock_steps(start_sample, clock).fill(out)
+    }
+
+    /// Fill `out` with the `Envelope`'s `y` value for every `x` step starting from `start`.
+    ///
+    /// This is equivalent to collecting `steps` into `out`, but avoids the per-step overhead of the
+    /// `Iterator`, making it suitable for rendering whole audio buffers at a time.
+    ///
+    /// Returns the number of values written, which is less than `out.len()` if the envelope fell
+    /// out of range. Any remaining values in `out` are left untouched.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn render(&'a self, start: Self::X, step: Self::X, out: &mut [Self::Y]) -> usize
+        where Self::X: Add<Output=Self::X> + Sub<Output=Self::X>,
+    {
+        match self.steps(start, step) {
+            Some(mut steps) => steps.fill(out),
+            None => 0,
+        }
+    }
+
+    /// A playhead positioned at the start of the `Envelope`.
+    ///
+    /// Unlike `steps`, a `Cursor` may be moved by any amount in either direction while
+    /// remembering the segment that it last landed in.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn cursor(&'a self) -> Cursor<'a, Self> {
+        Cursor::new(self)
+    }
+
+    /// The area beneath the `Envelope` between `x0` and `x1`, which is negative if `x1` comes
+    /// before `x0`.
+    ///
+    /// Segments with a known `Point::segment_shape` are integrated analytically, except for
+    /// eased segments, which are integrated numerically along with all segments of unknown shape.
+    ///
+    /// Returns `None` if either `x` lies out of range of the points. Any extrapolation is not
+    /// integrated.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn integrate(&'a self, x0: Self::X, x1: Self::X) -> Option<<Self::Y as Spatial>::Scalar>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        integral::integrate(self, x0, x1)
+    }
+
+    /// An iterator yielding the area beneath the `Envelope` from `start` to every following
+    /// `step`, landing on the same positions as `steps`.
+    ///
+    /// This is useful for accumulating quantities such as the phase of an oscillator from an
+    /// envelope of its frequency.
+    ///
+    /// Returns `None` if `start` lies out of range of the points.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn running_integral(&'a self, start: Self::X, step: Self::X)
+        -> Option<RunningIntegral<'a, Self>>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        RunningIntegral::new(self, start, step)
+    }
+
+    /// The slope of the `Envelope` at the given `x`.
+    ///
+    /// Segments with a known `Point::segment_shape` are differentiated analytically, except for
+    /// eased segments, which are differentiated numerically along with all segments of unknown
+    /// shape. Where `x` lies on a point, the slope of the segment beginning at that point is
+    /// returned, unless it is the last point.
+    ///
+    /// Returns `None` if `x` lies out of range of the points.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn dy_dx(&'a self, x: Self::X) -> Option<<Self::Y as Spatial>::Scalar>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        derivative::dy_dx(self, x)
+    }
+
+    /// The slope of the `Envelope` as a curve that may be evaluated at any `x`.
+    ///
+    /// See `dy_dx` for details.
+    #[inline]
+    fn derivative(&'a self) -> Derivative<'a, Self>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        Derivative::new(self)
+    }
+
+    /// The minimum and maximum `y` of the `Envelope` between `x0` and `x1` (inclusive).
+    ///
+    /// Any extrema within the segments are included, not only the points. These are found
+    /// analytically for bezier segments and numerically for eased segments and segments of
+    /// unknown shape. All other shapes are monotonic.
+    ///
+    /// Returns `None` if either `x` lies out of range of the points. See `range_index` for
+    /// answering many queries over a large envelope.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn y_range(&'a self, x0: Self::X, x1: Self::X) -> Option<(Self::Y, Self::Y)>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        range::y_range(self, x0, x1)
+    }
+
+    /// The range of the `Envelope` on the x axis and y axis, i.e. its bounding box.
+    ///
+    /// Returns `None` if the `Envelope` has no points.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn bounds(&'a self) -> Option<Bounds<Self::X, Self::Y>>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        let mut points = self.points();
+        let first = Breakpoint::x(points.next()?);
+        let last = points.next_back().map(Breakpoint::x).unwrap_or_else(|| first.clone());
+        let y = self.y_range(first.clone(), last.clone())?;
+        Some(Bounds { x: (first, last), y })
+    }
+
+    /// Precompute the range of every segment of the `Envelope` so that `y_range` may be queried
+    /// quickly.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn range_index(&'a self) -> RangeIndex<'a, Self>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        RangeIndex::new(self)
+    }
+
+    /// The `x` at which the `Envelope` produces the given `y`, for envelopes that are monotonic
+    /// over the range of their points.
+    ///
+    /// Segments with a known `Point::segment_shape` are solved analytically, except for eased
+    /// segments, which are solved numerically within the segment along with all segments of
+    /// unknown shape. If the `y` is held over some flat period, the start of the period is
+    /// returned.
+    ///
+    /// Every segment is checked for monotonicity on each call: analytically for known shapes, or
+    /// by sampling the slope across segments of unknown shape. See `inverse` for answering many
+    /// queries over a large envelope.
+    ///
+    /// Returns an `InverseError` if the envelope has no points, if it is not monotonic, or if the
+    /// `y` lies outside of its range. See `xs_at_y` for envelopes that are not monotonic.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn x_at_y(&'a self, y: Self::Y) -> Result<Self::X, InverseError>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        inverse::x_at_y(self, y)
+    }
+
+    /// Check that the `Envelope` is monotonic once so that `x_at_y` may be queried quickly.
+    ///
+    /// Returns an `InverseError` if the envelope has no points or if it is not monotonic.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn inverse(&'a self) -> Result<Inverse<'a, Self>, InverseError>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        Inverse::new(self)
+    }
+
+    /// A copy of the `Envelope`'s points with as many as possible removed while keeping `y`
+    /// within `tolerance` of the original at every `x`.
+    ///
+    /// Uses the Ramer–Douglas–Peucker algorithm with the deviation measured in `y` after
+    /// interpolation, including any extrema within curved segments. This is useful for thinning
+    /// the thousands of nearly collinear points captured when recording automation.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn simplify(&'a self, tolerance: <Self::Y as Spatial>::Scalar) -> Vec<Self::Point>
+        where Self::Point: Point<X=Self::X, Y=Self::Y> + Clone,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        simplify::simplify(self, tolerance)
+    }
+
+    /// An iterator yielding the X for each point at which the envelope intersects the given `y`.
+    ///
+    /// If there are any periods at which X is continuous, only the start X of the continuous
+    /// period will be returned.
+    ///
+    /// Segments with a known `Point::segment_shape` are solved analytically where possible. All
+    /// other segments are solved numerically via `Breakpoint::interpolate_segment`.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    #[inline]
+    fn xs_at_y(&'a self, y: Self::Y) -> XsAtY<'a, Self>
+        where Self::Point: Point<X=Self::X, Y=Self::Y>,
+              Self::X: NumCast,
+              Self::Y: NumCast + Spatial,
+              <Self::Y as Spatial>::Scalar: Float + Ease,
+    {
+        XsAtY::new(self, y)
+    }
 
 }
 
 
 /// An iterator that interpolates the envelope `E` one `step` and yields the result.
 ///
-/// Returns `None` the first time `next` falls out of range of all points in `env`.
+/// Returns `None` the first time `next` falls out of range of all points in `env`, unless `env`
+/// provides some extrapolation via `Envelope::y`.
 #[derive(Clone)]
 pub struct Steps<'a, E>
     where E: Envelope<'a> + 'a,
@@ -209,9 +549,12 @@ pub struct Steps<'a, E>
     points: E::Points,
     step: E::X,
     next_x: E::X,
+    /// The rounding error lost from `next_x` by the last step, if any.
+    compensation: Option<E::X>,
+    maybe_prev: Option<&'a E::Point>,
     left: &'a E::Point,
     maybe_right: Option<&'a E::Point>,
-    env: std::marker::PhantomData<E>,
+    env: &'a E,
 }
 
 impl<'a, E> Steps<'a, E>
@@ -233,38 +576,508 @@ impl<'a, E> Steps<'a, E>
     }
 }
 
+impl<'a, E> Steps<'a, E>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X>,
+{
+    /// Advance `next_x` by one step and return its previous value.
+    ///
+    /// The steps are accumulated with compensated (Kahan) summation so that rounding error does
+    /// not build up over long runs of steps, i.e. the `n`th step lands as close to
+    /// `start + n * step` as the precision of `X` allows.
+    #[inline]
+    fn advance(&mut self) -> E::X {
+        let x = self.next_x.clone();
+        let step = match self.compensation.take() {
+            Some(compensation) => self.step.clone() - compensation,
+            None => self.step.clone(),
+        };
+        let next_x = x.clone() + step.clone();
+        self.compensation = Some((next_x.clone() - x.clone()) - step);
+        self.next_x = next_x;
+        x
+    }
+
+    /// Fill `out` with the following steps.
+    ///
+    /// Steps that land within the current segment are interpolated in a tight loop, without the
+    /// per-step range checks performed by `next`.
+    ///
+    /// Returns the number of values written, which is less than `out.len()` if the steps fell out
+    /// of range. Any remaining values in `out` are left untouched.
+    pub fn fill(&mut self, out: &mut [E::Y]) -> usize {
+        let mut written = 0;
+        while written < out.len() {
+            // Interpolate every step that lands within the current segment.
+            if let Some(right) = self.maybe_right {
+                let (left_x, right_x) = (self.left.x(), right.x());
+                let maybe_next = self.points.clone().next();
+                for y in out[written..].iter_mut() {
+                    let x = self.next_x.clone();
+                    if x < left_x || x > right_x {
+                        break;
+                    }
+                    self.advance();
+                    *y = Breakpoint::interpolate_segment(x, self.maybe_prev, self.left, right,
+                                                         maybe_next);
+                    written += 1;
+                }
+                if written == out.len() {
+                    break;
+                }
+            }
+
+            // Move on to the next segment, or fall back to the envelope's extrapolation.
+            match self.next() {
+                Some(y) => out[written] = y,
+                None => break,
+            }
+            written += 1;
+        }
+        written
+    }
+}
+
 impl<'a, E> Iterator for Steps<'a, E>
     where E: Envelope<'a>,
-          <E as Envelope<'a>>::X: std::ops::Add<Output=<E as Envelope<'a>>::X>,
-          <<E as Envelope<'a>>::Y as Spatial>::Scalar: Scalar,
+          E::X: Add<Output=E::X> + Sub<Output=E::X>,
 {
     type Item = E::Y;
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let Steps {
-            ref step,
-            ref mut points,
-            ref mut next_x,
-            ref mut left,
-            ref mut maybe_right,
-            ..
-        } = *self;
-
-        let x = next_x.clone();
-        *next_x = x.clone() + step.clone();
-        maybe_right.as_mut()
-            .and_then(|right| {
-                let x = x.clone();
+        let x = self.advance();
+        if x >= self.left.x() {
+            if let Some(mut right) = self.maybe_right {
                 while x > right.x() {
-                    *left = right;
-                    *right = match points.next() {
-                        Some(point) => point,
-                        None => return None,
+                    self.maybe_prev = Some(self.left);
+                    self.left = right;
+                    match self.points.next() {
+                        Some(point) => right = point,
+                        None => {
+                            self.maybe_right = None;
+                            return self.env.y(x);
+                        },
+                    }
+                }
+                self.maybe_right = Some(right);
+                let maybe_next = self.points.clone().next();
+                return Some(Breakpoint::interpolate_segment(x, self.maybe_prev, self.left, right,
+                                                            maybe_next));
+            }
+        }
+        // `x` lies beyond the points, so defer to the envelope for any extrapolation.
+        self.env.y(x)
+    }
+}
+
+
+/// An iterator that samples the envelope `E` at each consecutive integer sample index for some
+/// sample rate.
+///
+/// Yields `None` for any sample that falls out of range of all points in `env`, unless `env`
+/// provides some extrapolation via `Envelope::y`.
+#[derive(Clone)]
+pub struct SampleSteps<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    stepper: SampleStepper<'a, E>,
+    sample_rate: f64,
+}
+
+impl<'a, E> SampleSteps<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+{
+    /// The index of the next sample.
+    #[inline]
+    pub fn sample(&self) -> u64 {
+        self.stepper.sample
+    }
+
+    /// The number of samples per unit of `X`.
+    #[inline]
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Move to the sample at the given index.
+    #[inline]
+    pub fn set_sample(&mut self, sample: u64) {
+        self.stepper.sample = sample;
+    }
+
+    /// The `x` at which the sample with the given index lies.
+    #[inline]
+    pub fn x_at(&self, sample: u64) -> E::X {
+        scalar(sample as f64 / self.sample_rate)
+    }
+
+    /// Fill `out` with the following samples.
+    ///
+    /// Returns the number of values written, which is less than `out.len()` if the samples fell
+    /// out of range. Any remaining values in `out` are left untouched.
+    #[inline]
+    pub fn fill(&mut self, out: &mut [E::Y]) -> usize {
+        let sample_rate = self.sample_rate;
+        self.stepper.fill(out, |sample| scalar(sample as f64 / sample_rate))
+    }
+}
+
+impl<'a, E> Iterator for SampleSteps<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+{
+    type Item = E::Y;
+    #[inline]
+    fn next(&mut self) -> Option<E::Y> {
+        let sample_rate = self.sample_rate;
+        self.stepper.next(|sample| scalar(sample as f64 / sample_rate))
+    }
+}
+
+
+/// An iterator that samples the envelope `E` at each consecutive integer sample index of some
+/// `SampleClock`.
+///
+/// Yields `None` for any sample that falls out of range of all points in `env`, unless `env`
+/// provides some extrapolation via `Envelope::y`.
+#[derive(Clone)]
+pub struct ClockSteps<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    stepper: SampleStepper<'a, E>,
+    clock: SampleClock,
+}
+
+impl<'a, E> ClockSteps<'a, E>
+    where E: Envelope<'a>,
+          E::X: Time,
+{
+    /// The index of the next sample.
+    #[inline]
+    pub fn sample(&self) -> u64 {
+        self.stepper.sample
+    }
+
+    /// The clock mapping each sample index to its `x`.
+    #[inline]
+    pub fn clock(&self) -> SampleClock {
+        self.clock
+    }
+
+    /// Move to the sample at the given index.
+    #[inline]
+    pub fn set_sample(&mut self, sample: u64) {
+        self.stepper.sample = sample;
+    }
+
+    /// The `x` at which the sample with the given index lies.
+    #[inline]
+    pub fn x_at(&self, sample: u64) -> E::X {
+        self.clock.x_at(sample)
+    }
+
+    /// Fill `out` with the following samples.
+    ///
+    /// Returns the number of values written, which is less than `out.len()` if the samples fell
+    /// out of range. Any remaining values in `out` are left untouched.
+    #[inline]
+    pub fn fill(&mut self, out: &mut [E::Y]) -> usize {
+        let clock = self.clock;
+        self.stepper.fill(out, |sample| clock.x_at(sample))
+    }
+}
+
+impl<'a, E> Iterator for ClockSteps<'a, E>
+    where E: Envelope<'a>,
+          E::X: Time,
+{
+    type Item = E::Y;
+    #[inline]
+    fn next(&mut self) -> Option<E::Y> {
+        let clock = self.clock;
+        self.stepper.next(|sample| clock.x_at(sample))
+    }
+}
+
+
+/// The stepping shared by `SampleSteps` and `ClockSteps`, which differ only in how a sample index
+/// is mapped to its `x`.
+#[derive(Clone)]
+struct SampleStepper<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    cursor: Cursor<'a, E>,
+    /// The index of the next sample.
+    sample: u64,
+}
+
+impl<'a, E> SampleStepper<'a, E>
+    where E: Envelope<'a>,
+{
+    #[inline]
+    fn new(env: &'a E, sample: u64) -> Self {
+        SampleStepper { cursor: env.cursor(), sample }
+    }
+
+    /// The `y` of the next sample, whose `x` is given by `x_at`.
+    #[inline]
+    fn next<F>(&mut self, x_at: F) -> Option<E::Y>
+        where F: Fn(u64) -> E::X,
+    {
+        let x = x_at(self.sample);
+        self.sample += 1;
+        self.cursor.y_at(x)
+    }
+
+    fn fill<F>(&mut self, out: &mut [E::Y], x_at: F) -> usize
+        where F: Fn(u64) -> E::X,
+    {
+        for (written, y) in out.iter_mut().enumerate() {
+            match self.next(&x_at) {
+                Some(next) => *y = next,
+                None => return written,
+            }
+        }
+        out.len()
+    }
+}
+
+
+/// A playhead over the envelope `E` that remembers the segment in which it last landed.
+///
+/// Seeking to a nearby `x` only visits the points between the old and new positions, so
+/// evaluating the envelope at sequential positions (e.g. once per audio block) is amortized O(1)
+/// regardless of the distance travelled between each call.
+///
+/// Seeking backwards is equally cheap for envelopes that provide a `points_slice`. Otherwise the
+/// cursor must rescan from the first point.
+#[derive(Clone)]
+pub struct Cursor<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    env: &'a E,
+    /// The index of `maybe_left` within the envelope's points.
+    idx: usize,
+    maybe_prev: Option<&'a E::Point>,
+    maybe_left: Option<&'a E::Point>,
+    maybe_right: Option<&'a E::Point>,
+    /// The points that follow `maybe_right`.
+    points: E::Points,
+}
+
+impl<'a, E> Cursor<'a, E>
+    where E: Envelope<'a>,
+{
+    /// Construct a `Cursor` positioned at the first segment of the given envelope.
+    #[inline]
+    pub fn new(env: &'a E) -> Self {
+        let mut points = env.points();
+        let maybe_left = points.next();
+        let maybe_right = points.next();
+        Cursor {
+            env,
+            idx: 0,
+            maybe_prev: None,
+            maybe_left,
+            maybe_right,
+            points,
+        }
+    }
+
+    /// The index of the point at which the current segment begins.
+    #[inline]
+    pub fn segment_idx(&self) -> usize {
+        self.idx
+    }
+
+    /// The points at the start and end of the current segment.
+    #[inline]
+    pub fn segment(&self) -> (Option<&'a E::Point>, Option<&'a E::Point>) {
+        (self.maybe_left, self.maybe_right)
+    }
+
+    /// Move the playhead to the segment containing the given `x`.
+    ///
+    /// Returns the number of point boundaries that were crossed in order to reach `x` from the
+    /// previous position, in either direction. A return value greater than `0` indicates that the
+    /// playhead has landed in a new segment.
+    pub fn seek(&mut self, x: E::X) -> usize {
+        let start_idx = self.idx;
+
+        // Step backwards until the left point comes before `x`.
+        let needs_rewind = match self.maybe_left {
+            Some(left) => self.idx > 0 && left.x() >= x,
+            None => false,
+        };
+        if needs_rewind {
+            match self.env.points_slice() {
+                Some(slice) => {
+                    let mut idx = self.idx;
+                    while idx > 0 && slice[idx].x() >= x {
+                        idx -= 1;
+                    }
+                    let mut points = self.env.points();
+                    self.maybe_prev = idx.checked_sub(1).map(|i| &slice[i]);
+                    self.maybe_left = points.nth(idx);
+                    self.maybe_right = points.next();
+                    self.points = points;
+                    self.idx = idx;
+                },
+                None => *self = Cursor::new(self.env),
+            }
+        }
+
+        // Step forwards until the right point lands on or after `x`.
+        while let Some(right) = self.maybe_right {
+            if right.x() < x {
+                self.maybe_prev = self.maybe_left;
+                self.maybe_left = Some(right);
+                self.maybe_right = self.points.next();
+                self.idx += 1;
+            } else {
+                break;
+            }
+        }
+
+        self.idx.abs_diff(start_idx)
+    }
+
+    /// Move the playhead to the given `x` and return the envelope's `y` value at that position.
+    ///
+    /// The result is the same as that of `Envelope::y`.
+    #[inline]
+    pub fn y_at(&mut self, x: E::X) -> Option<E::Y> {
+        self.seek(x.clone());
+        match (self.maybe_left, self.maybe_right) {
+            (Some(left), Some(right)) if x >= left.x() => {
+                let maybe_next = self.points.clone().next();
+                Some(Breakpoint::interpolate_segment(x, self.maybe_prev, left, right, maybe_next))
+            },
+            // `x` lies on the last point or beyond the points.
+            _ => self.env.y(x),
+        }
+    }
+}
+
+
+/// An iterator yielding each `x` at which an envelope intersects some `y`.
+///
+/// See `Envelope::xs_at_y` for details.
+pub struct XsAtY<'a, E>
+    where E: Envelope<'a> + 'a,
+          E::Y: Spatial,
+{
+    points: E::Points,
+    maybe_prev: Option<&'a E::Point>,
+    maybe_left: Option<&'a E::Point>,
+    y: <E::Y as Spatial>::Scalar,
+    /// The solutions that are yet to be yielded.
+    xs: VecDeque<<E::Y as Spatial>::Scalar>,
+    /// A buffer for solving each segment.
+    segment_xs: Vec<<E::Y as Spatial>::Scalar>,
+    last_x: Option<<E::Y as Spatial>::Scalar>,
+    /// Whether or not the previous segment lies flat on `y`.
+    was_flat: bool,
+}
+
+impl<'a, E> XsAtY<'a, E>
+    where E: Envelope<'a>,
+          E::Point: Point<X=E::X, Y=E::Y>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+{
+    fn new(env: &'a E, y: E::Y) -> Self {
+        let mut points = env.points();
+        let maybe_left = points.next();
+        let mut xs = VecDeque::new();
+
+        // A lone point has no segments to solve.
+        if let Some(point) = maybe_left {
+            if points.len() == 0 && Breakpoint::y(point) == y {
+                xs.push_back(E::Point::x_to_scalar(Breakpoint::x(point)));
+            }
+        }
+
+        XsAtY {
+            points,
+            maybe_prev: None,
+            maybe_left,
+            y: scalar(y),
+            xs,
+            segment_xs: Vec::new(),
+            last_x: None,
+            was_flat: false,
+        }
+    }
+}
+
+impl<'a, E> Iterator for XsAtY<'a, E>
+    where E: Envelope<'a>,
+          E::Point: Point<X=E::X, Y=E::Y>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+{
+    type Item = E::X;
+    fn next(&mut self) -> Option<E::X> {
+        loop {
+            if let Some(x) = self.xs.pop_front() {
+                return Some(scalar(x));
+            }
+
+            let (left, right) = match (self.maybe_left, self.points.next()) {
+                (Some(left), Some(right)) => (left, right),
+                _ => return None,
+            };
+            let maybe_prev = self.maybe_prev;
+            let maybe_next = self.points.clone().next();
+            self.maybe_prev = Some(left);
+            self.maybe_left = Some(right);
+
+            let y = self.y;
+            let start = (E::Point::x_to_scalar(Breakpoint::x(left)), scalar(Breakpoint::y(left)));
+            let end = (E::Point::x_to_scalar(Breakpoint::x(right)), scalar(Breakpoint::y(right)));
+            self.segment_xs.clear();
+            match Point::segment_shape(left, right) {
+                Some(shape) => shape.xs_at_y(start, end, y, &mut self.segment_xs),
+                // Vertical segments are the same for all shapes. Without a known shape, even a
+                // segment between equal `y`s may bend between them.
+                None => if start.0 == end.0 {
+                    SegmentShape::Linear.xs_at_y(start, end, y, &mut self.segment_xs);
+                } else {
+                    let f = |x| {
+                        let y_at_x: <E::Y as Spatial>::Scalar =
+                            scalar(Breakpoint::interpolate_segment(scalar(x), maybe_prev, left,
+                                                                   right, maybe_next));
+                        y_at_x - y
                     };
+                    numeric::roots(&f, start.0, end.0, &mut self.segment_xs);
+                    // Only keep the start of any continuous period at `y`.
+                    let (zero, two): (<E::Y as Spatial>::Scalar, _) = (scalar(0.0), scalar(2.0));
+                    let mut maybe_last = None;
+                    self.segment_xs.retain(|&x| {
+                        let is_continuous =
+                            maybe_last.is_some_and(|last| f((last + x) / two) == zero);
+                        maybe_last = Some(x);
+                        !is_continuous
+                    });
+                },
+            }
+
+            // Skip the end of a continuous period along with any solutions already yielded at
+            // the end of the previous segment.
+            let was_flat = self.was_flat;
+            self.was_flat = start.1 == y && end.1 == y;
+            for &x in &self.segment_xs {
+                if (was_flat && x == start.0) || self.last_x == Some(x) {
+                    continue;
                 }
-                Some(Point::interpolate(x, *left, *right))
-            })
-            .or_else(|| if x == left.x() { Some(left.y()) } else { None })
+                self.last_x = Some(x);
+                self.xs.push_back(x);
+            }
+        }
     }
 }
 
@@ -273,10 +1086,16 @@ impl<'a, E> Iterator for Steps<'a, E>
 fn point_idx_before<'a, E>(env: &'a E, x: E::X) -> Option<usize>
     where E: Envelope<'a>,
 {
-    env.points().enumerate()
-        .take_while(|&(_, point)| point.x() < x )
-        .last()
-        .map(|(i, _)| i)
+    match env.points_slice() {
+        Some(points) => match points.partition_point(|point| point.x() < x) {
+            0 => None,
+            i => Some(i - 1),
+        },
+        None => env.points().enumerate()
+            .take_while(|&(_, point)| point.x() < x )
+            .last()
+            .map(|(i, _)| i),
+    }
 }
 
 
@@ -284,10 +1103,16 @@ fn point_idx_before<'a, E>(env: &'a E, x: E::X) -> Option<usize>
 fn point_idx_on_or_before<'a, E>(env: &'a E, x: E::X) -> Option<usize>
     where E: Envelope<'a>,
 {
-    env.points().enumerate()
-        .take_while(|&(_, point)| point.x() <= x )
-        .last()
-        .map(|(i, _)| i)
+    match env.points_slice() {
+        Some(points) => match points.partition_point(|point| point.x() <= x) {
+            0 => None,
+            i => Some(i - 1),
+        },
+        None => env.points().enumerate()
+            .take_while(|&(_, point)| point.x() <= x )
+            .last()
+            .map(|(i, _)| i),
+    }
 }
 
 
@@ -295,10 +1120,16 @@ fn point_idx_on_or_before<'a, E>(env: &'a E, x: E::X) -> Option<usize>
 fn point_idx_after<'a, E>(env: &'a E, x: E::X) -> Option<usize>
     where E: Envelope<'a>,
 {
-    env.points().enumerate().rev()
-        .take_while(|&(_, point)| point.x() > x )
-        .last()
-        .map(|(i, _)| i)
+    match env.points_slice() {
+        Some(points) => match points.partition_point(|point| point.x() <= x) {
+            i if i < points.len() => Some(i),
+            _ => None,
+        },
+        None => env.points().enumerate().rev()
+            .take_while(|&(_, point)| point.x() > x )
+            .last()
+            .map(|(i, _)| i),
+    }
 }
 
 
@@ -306,34 +1137,64 @@ fn point_idx_after<'a, E>(env: &'a E, x: E::X) -> Option<usize>
 fn point_idx_on_or_after<'a, E>(env: &'a E, x: E::X) -> Option<usize>
     where E: Envelope<'a>,
 {
-    env.points().enumerate().rev()
-        .take_while(|&(_, point)| point.x() >= x )
-        .last()
-        .map(|(i, _)| i)
+    match env.points_slice() {
+        Some(points) => match points.partition_point(|point| point.x() < x) {
+            i if i < points.len() => Some(i),
+            _ => None,
+        },
+        None => env.points().enumerate().rev()
+            .take_while(|&(_, point)| point.x() >= x )
+            .last()
+            .map(|(i, _)| i),
+    }
 }
 
 
 #[inline]
 fn y<'a, E>(env: &'a E, x: E::X) -> Option<E::Y>
     where E: Envelope<'a>,
-          E::Y: Spatial + PartialEq + 'a,
-          <<E as Envelope<'a>>::Y as Spatial>::Scalar: Scalar,
+          E::Y: PartialEq + 'a,
 {
+    // With random access to the points we can find the surrounding segment via binary search.
+    if let Some(points) = env.points_slice() {
+        let left_idx = match points.partition_point(|point| point.x() < x) {
+            0 => match points.first() {
+                Some(first) => if x < first.x() { return None; } else { 0 },
+                None => return None,
+            },
+            i => i - 1,
+        };
+        let left = &points[left_idx];
+        return match points.get(left_idx + 1) {
+            Some(right) => {
+                let maybe_prev = left_idx.checked_sub(1).map(|i| &points[i]);
+                let maybe_next = points.get(left_idx + 2);
+                Some(Breakpoint::interpolate_segment(x, maybe_prev, left, right, maybe_next))
+            },
+            None => if x == left.x() { Some(left.y()) } else { None },
+        };
+    }
+
     let mut points = env.points();
     points.next().and_then(|mut left| {
+        let mut maybe_prev = None;
         let mut maybe_right = None;
 
         // Iterate through `points` until `x` is between `left` and `right`
-        while let Some(point) = points.next() {
+        for point in points.by_ref() {
             maybe_right = Some(point);
             if point.x() < x {
+                maybe_prev = Some(left);
                 left = maybe_right.take().unwrap();
             } else {
                 break;
             }
         }
 
-        // Check that the remaining points bound the `x`.
+        // Check that the points bound the `x`.
+        if x < left.x() {
+            return None;
+        }
         match maybe_right {
             Some(right) => if right.x() < x { return None; },
             None => if left.x() < x { return None; },
@@ -343,13 +1204,14 @@ fn y<'a, E>(env: &'a E, x: E::X) -> Option<E::Y>
             .and_then(|mut right| {
                 let x = x.clone();
                 while x > right.x() {
+                    maybe_prev = Some(left);
                     left = right;
                     right = match points.next() {
                         Some(point) => point,
                         None => return None,
                     };
                 }
-                Some(Point::interpolate(x, left, right))
+                Some(Breakpoint::interpolate_segment(x, maybe_prev, left, right, points.next()))
             })
             .or_else(|| if x == left.x() { Some(left.y()) } else { None })
     })
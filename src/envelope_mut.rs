@@ -0,0 +1,130 @@
+use containers::cmp_x;
+use point::{Breakpoint, PointMut};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+
+/// Types that own an editable sequence of `Point`s sorted by `x`.
+///
+/// Every method maintains the ordering of the points. When a point is inserted at the same `x`
+/// as one or more existing points, it is placed *after* them. This way points that share an `x`
+/// (i.e. a vertical jump) remain in the order in which they were added.
+///
+/// Any point with an `x` that cannot be compared with itself (i.e. NaN) is placed after all
+/// others, as when sorting the points of a `VecEnvelope`.
+pub trait EnvelopeMut {
+    type X: PartialOrd + Clone;
+    type Y: PartialEq;
+    /// The `Point` type owned by the `EnvelopeMut`.
+    type Point: Breakpoint<X=Self::X, Y=Self::Y>;
+
+    /// Insert the given `point` at its sorted position and return the index at which it was
+    /// inserted.
+    fn insert_point(&mut self, point: Self::Point) -> usize;
+
+    /// Remove and return the point at the given index if there is one.
+    fn remove_point(&mut self, idx: usize) -> Option<Self::Point>;
+
+    /// Move the point at the given index to `x` and set its value to `y`.
+    ///
+    /// If `x` is unchanged, `y` is written in place and the point keeps its index, so that a
+    /// vertical jump keeps its direction. Otherwise the point is re-sorted as though it were
+    /// removed and inserted again, placing it after any existing points at the new `x`. Returns the
+    /// new index of the point, or `None` if there was no point at the given index.
+    fn move_point(&mut self, idx: usize, x: Self::X, y: Self::Y) -> Option<usize>
+        where Self::Point: PointMut;
+
+    /// Retain only the points for which the given predicate returns `true`.
+    fn retain<F>(&mut self, f: F)
+        where F: FnMut(&Self::Point) -> bool;
+}
+
+
+impl<P> EnvelopeMut for Vec<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    type X = P::X;
+    type Y = P::Y;
+    type Point = P;
+
+    #[inline]
+    fn insert_point(&mut self, point: P) -> usize {
+        let x = point.x();
+        let idx = self.partition_point(|p| cmp_x(&p.x(), &x) != Ordering::Greater);
+        self.insert(idx, point);
+        idx
+    }
+
+    #[inline]
+    fn remove_point(&mut self, idx: usize) -> Option<P> {
+        if idx < self.len() { Some(self.remove(idx)) } else { None }
+    }
+
+    #[inline]
+    fn move_point(&mut self, idx: usize, x: P::X, y: P::Y) -> Option<usize>
+        where P: PointMut,
+    {
+        if self.get(idx)?.x() == x {
+            self[idx].set_y(y);
+            return Some(idx);
+        }
+        self.remove_point(idx).map(|mut point| {
+            point.set_x(x);
+            point.set_y(y);
+            self.insert_point(point)
+        })
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, f: F)
+        where F: FnMut(&P) -> bool,
+    {
+        Vec::retain(self, f)
+    }
+}
+
+
+impl<P> EnvelopeMut for VecDeque<P>
+    where P: Breakpoint,
+          P::X: PartialOrd,
+{
+    type X = P::X;
+    type Y = P::Y;
+    type Point = P;
+
+    #[inline]
+    fn insert_point(&mut self, point: P) -> usize {
+        let x = point.x();
+        let idx = self.partition_point(|p| cmp_x(&p.x(), &x) != Ordering::Greater);
+        self.insert(idx, point);
+        idx
+    }
+
+    #[inline]
+    fn remove_point(&mut self, idx: usize) -> Option<P> {
+        self.remove(idx)
+    }
+
+    #[inline]
+    fn move_point(&mut self, idx: usize, x: P::X, y: P::Y) -> Option<usize>
+        where P: PointMut,
+    {
+        if self.get(idx)?.x() == x {
+            self[idx].set_y(y);
+            return Some(idx);
+        }
+        self.remove_point(idx).map(|mut point| {
+            point.set_x(x);
+            point.set_y(y);
+            self.insert_point(point)
+        })
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, f: F)
+        where F: FnMut(&P) -> bool,
+    {
+        VecDeque::retain(self, f)
+    }
+}
@@ -0,0 +1,125 @@
+use envelope::Envelope;
+use point::Breakpoint;
+use std::ops::{Add, Rem, Sub};
+
+
+/// Describes how an `Envelope` is evaluated at an `x` that lies outside the range of its points.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Extrapolation {
+    /// There is no value outside the range of the points.
+    None,
+    /// Hold the `y` of the first point before the range and of the last point after it.
+    Hold,
+    /// Extend the slope of the first and last segments in a straight line.
+    Linear,
+    /// Repeat the envelope from the first point to the last point.
+    Loop,
+    /// Repeat the envelope, alternating between playing forwards and backwards.
+    PingPong,
+}
+
+/// An `Envelope` that evaluates `x` outside the range of its points using some `Extrapolation`.
+///
+/// The extrapolation applies to `y`, along with the `Steps` and `Cursor` produced by the envelope.
+/// See `Envelope::extrapolate`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Extrapolated<E> {
+    /// The envelope being extrapolated.
+    pub env: E,
+    /// The extrapolation used for `x` outside the range of the points.
+    pub extrapolation: Extrapolation,
+}
+
+
+impl<E> Extrapolated<E> {
+    /// Wrap the given envelope with the given `Extrapolation`.
+    #[inline]
+    pub fn new(env: E, extrapolation: Extrapolation) -> Self {
+        Extrapolated { env, extrapolation }
+    }
+}
+
+impl<'a, E> Envelope<'a> for Extrapolated<E>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X> + Rem<Output=E::X>,
+{
+    type X = E::X;
+    type Y = E::Y;
+    type Point = E::Point;
+    type Points = E::Points;
+    #[inline]
+    fn points(&'a self) -> Self::Points {
+        self.env.points()
+    }
+    #[inline]
+    fn points_slice(&'a self) -> Option<&'a [Self::Point]> {
+        self.env.points_slice()
+    }
+    #[inline]
+    fn y(&'a self, x: Self::X) -> Option<Self::Y> {
+        self.env.y_extrapolated(x, self.extrapolation)
+    }
+}
+
+
+/// Return `y` for the given `x`, extrapolating beyond the points with the given method.
+pub fn y<'a, E>(env: &'a E, x: E::X, extrapolation: Extrapolation) -> Option<E::Y>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X> + Rem<Output=E::X>,
+{
+    let points = env.points();
+    let (first, last) = match (points.clone().next(), points.clone().next_back()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return None,
+    };
+    let (first_x, last_x) = (first.x(), last.x());
+    if x >= first_x && x <= last_x {
+        return env.y(x);
+    }
+
+    let is_before = x < first_x;
+    let len = last_x.clone() - first_x.clone();
+    let zero = first_x.clone() - first_x.clone();
+    match extrapolation {
+        Extrapolation::None => None,
+
+        Extrapolation::Hold => Some(if is_before { first.y() } else { last.y() }),
+
+        Extrapolation::Linear => {
+            let (start, end) = if is_before {
+                (first, points.clone().nth(1))
+            } else {
+                (last, points.clone().rev().nth(1))
+            };
+            match end {
+                Some(end) if end.x() != start.x() =>
+                    Some(Breakpoint::extrapolate_segment(x, start, end)),
+                _ => Some(start.y()),
+            }
+        },
+
+        Extrapolation::Loop => {
+            if len == zero {
+                return Some(first.y());
+            }
+            let x = if is_before {
+                let r = (first_x.clone() - x) % len;
+                if r == zero { first_x } else { last_x - r }
+            } else {
+                first_x.clone() + (x - first_x) % len
+            };
+            env.y(x)
+        },
+
+        Extrapolation::PingPong => {
+            if len == zero {
+                return Some(first.y());
+            }
+            // The envelope is symmetric about the first point with a period of two lengths.
+            let distance = if is_before { first_x.clone() - x } else { x - first_x.clone() };
+            let r = distance % (len.clone() + len.clone());
+            let x = if r <= len { first_x + r } else { last_x - (r - len) };
+            env.y(x)
+        },
+    }
+}
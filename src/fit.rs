@@ -0,0 +1,357 @@
+use any_point::AnyPoint;
+use bezier_point::BezierPoint;
+use cubic_bezier_point::CubicBezierPoint;
+use ease_point::EasePoint;
+use hermite_point::HermitePoint;
+use interpolate::CatmullRom;
+use interpolation::{Ease, EaseFunction, Spatial};
+use num::{Float, NumCast};
+use numeric::scalar;
+use point::{Breakpoint, Point};
+use shape::SegmentShape;
+use spline_point::{Spline, SplinePoint};
+use std::collections::BTreeSet;
+use std::iter::once;
+
+
+/// Every `EaseFunction`, in the order in which they are tried when fitting.
+const EASE_FUNCTIONS: [EaseFunction; 30] = [
+    EaseFunction::QuadraticIn, EaseFunction::QuadraticOut, EaseFunction::QuadraticInOut,
+    EaseFunction::CubicIn, EaseFunction::CubicOut, EaseFunction::CubicInOut,
+    EaseFunction::QuarticIn, EaseFunction::QuarticOut, EaseFunction::QuarticInOut,
+    EaseFunction::QuinticIn, EaseFunction::QuinticOut, EaseFunction::QuinticInOut,
+    EaseFunction::SineIn, EaseFunction::SineOut, EaseFunction::SineInOut,
+    EaseFunction::CircularIn, EaseFunction::CircularOut, EaseFunction::CircularInOut,
+    EaseFunction::ExponentialIn, EaseFunction::ExponentialOut, EaseFunction::ExponentialInOut,
+    EaseFunction::ElasticIn, EaseFunction::ElasticOut, EaseFunction::ElasticInOut,
+    EaseFunction::BackIn, EaseFunction::BackOut, EaseFunction::BackInOut,
+    EaseFunction::BounceIn, EaseFunction::BounceOut, EaseFunction::BounceInOut,
+];
+
+
+/// Point types whose shape may be fitted to a run of sampled `(x, y)` values.
+///
+/// `StepPoint` is not a `Point` and so cannot be fitted.
+pub trait FitPoint: Point {
+    /// A point at the given `sample` shaped to follow the samples on either side of it.
+    ///
+    /// `before` holds the samples from the previous point up to (but excluding) `sample`, while
+    /// `after` holds the samples following `sample` up to and including the next point. Either is
+    /// empty if there is no point on that side.
+    fn fit(before: &[(Self::X, Self::Y)],
+           sample: (Self::X, Self::Y),
+           after: &[(Self::X, Self::Y)]) -> Self;
+}
+
+
+/// The result of fitting points to a sequence of samples.
+#[derive(Clone, PartialEq)]
+pub struct Fit<P>
+    where P: Point,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    /// The fitted points, one for each sample that was kept.
+    pub points: Vec<P>,
+    /// The greatest difference in `y` between the fitted curve and any of the samples.
+    pub max_error: <P::Y as Spatial>::Scalar,
+}
+
+
+impl<P> Fit<P>
+    where P: FitPoint,
+          P::Y: NumCast + Clone,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    /// Fit as few points as possible to the given samples while keeping the curve within
+    /// `tolerance` of every sample's `y`.
+    ///
+    /// Fitting begins with a single segment between the first and last samples. Each segment that
+    /// misses one of its samples by more than `tolerance` is split at its worst sample, after which
+    /// all points are refitted, as the shape of each point may depend on both of its segments.
+    /// This repeats until every segment is within `tolerance`. The error is measured at the
+    /// samples only.
+    ///
+    /// Note: The samples must be ordered by `x`.
+    pub fn new(samples: &[(P::X, P::Y)], tolerance: <P::Y as Spatial>::Scalar) -> Fit<P> {
+        let zero: <P::Y as Spatial>::Scalar = scalar(0.0);
+        if samples.is_empty() {
+            return Fit { points: Vec::new(), max_error: zero };
+        }
+
+        let mut kept: BTreeSet<usize> = vec![0, samples.len() - 1].into_iter().collect();
+        loop {
+            let indices: Vec<usize> = kept.iter().cloned().collect();
+            let points: Vec<P> = indices.iter().enumerate().map(|(k, &i)| {
+                let before = match k.checked_sub(1) {
+                    Some(k) => &samples[indices[k]..i],
+                    None => &samples[..0],
+                };
+                let after = match indices.get(k + 1) {
+                    Some(&next) => &samples[i + 1..next + 1],
+                    None => &samples[..0],
+                };
+                P::fit(before, samples[i].clone(), after)
+            }).collect();
+
+            let mut max_error = zero;
+            let mut splits = Vec::new();
+            for k in 0..indices.len() - 1 {
+                let (prev, next) = (k.checked_sub(1).map(|k| &points[k]), points.get(k + 2));
+                let (start, end) = (&points[k], &points[k + 1]);
+                let error = |j: usize| {
+                    let (x, y) = samples[j].clone();
+                    let fitted = Breakpoint::interpolate_segment(x, prev, start, end, next);
+                    (scalar::<<P::Y as Spatial>::Scalar, _>(fitted) - scalar(y)).abs()
+                };
+                let (worst, worst_error) = (indices[k] + 1..indices[k + 1])
+                    .map(|j| (j, error(j)))
+                    .fold((indices[k], zero),
+                          |worst, (j, e)| if e > worst.1 { (j, e) } else { worst });
+                max_error = max_error.max(worst_error);
+                if worst_error > tolerance {
+                    splits.push(worst);
+                }
+            }
+
+            if splits.is_empty() {
+                return Fit { points, max_error };
+            }
+            kept.extend(splits);
+        }
+    }
+}
+
+
+/// The greatest difference in `y` between the samples of `span` and the segment fitted between
+/// its first and last samples.
+///
+/// `before` holds the samples of the preceding segment, as given to `FitPoint::fit`.
+pub(crate) fn span_error<P>(before: &[(P::X, P::Y)], span: &[(P::X, P::Y)])
+    -> <P::Y as Spatial>::Scalar
+    where P: FitPoint,
+          P::Y: NumCast + Clone,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    let zero: <P::Y as Spatial>::Scalar = scalar(0.0);
+    if span.len() <= 2 {
+        return zero;
+    }
+    let last = span.len() - 1;
+    let prev = before.first().map(|sample| P::fit(&[], sample.clone(), &[]));
+    let start = P::fit(before, span[0].clone(), &span[1..]);
+    let end = P::fit(&span[..last], span[last].clone(), &[]);
+    span[1..last].iter().fold(zero, |max, (x, y)| {
+        let fitted = Breakpoint::interpolate_segment(x.clone(), prev.as_ref(), &start, &end, None);
+        max.max((scalar::<<P::Y as Spatial>::Scalar, _>(fitted) - scalar(y.clone())).abs())
+    })
+}
+
+
+/// Convert each sample to a pair of scalars.
+fn scalars<X, Y, S>(samples: &[(X, Y)]) -> Vec<(S, S)>
+    where X: NumCast + Copy,
+          Y: NumCast + Copy,
+          S: Float,
+{
+    samples.iter().map(|&(x, y)| (scalar(x), scalar(y))).collect()
+}
+
+
+/// The candidate whose segment towards the last sample of `after` best fits the samples before
+/// it, in the least-squares sense.
+///
+/// The first candidate is returned if there are no samples to fit.
+fn best_fit<P, I, F>(before: &[(P::X, P::Y)], after: &[(P::X, P::Y)], candidates: I, new: F) -> P
+    where P: Point,
+          P::X: Copy,
+          P::Y: NumCast + Copy,
+          <P::Y as Spatial>::Scalar: Float,
+          I: IntoIterator<Item=P>,
+          F: Fn(P::X, P::Y) -> P,
+{
+    let mut candidates = candidates.into_iter();
+    let first = candidates.next().expect("there must be at least one candidate");
+    let (&(end_x, end_y), interior) = match after.split_last() {
+        Some(split) => split,
+        None => return first,
+    };
+    let prev = before.first().map(|&(x, y)| new(x, y));
+    let end = new(end_x, end_y);
+    let zero: <P::Y as Spatial>::Scalar = scalar(0.0);
+    let squared_error = |start: &P| interior.iter().fold(zero, |sum, &(x, y)| {
+        let fitted = Breakpoint::interpolate_segment(x, prev.as_ref(), start, &end, None);
+        let error = scalar::<<P::Y as Spatial>::Scalar, _>(fitted) - scalar(y);
+        sum + error * error
+    });
+    let first_error = squared_error(&first);
+    candidates
+        .map(|candidate| {
+            let error = squared_error(&candidate);
+            (candidate, error)
+        })
+        .fold((first, first_error), |best, (candidate, error)| {
+            if error < best.1 { (candidate, error) } else { best }
+        })
+        .0
+}
+
+
+/// The least-squares `curve` of a quadratic bezier segment from `start` to the last sample of
+/// `after`.
+fn bezier_curve<S>(start: (S, S), after: &[(S, S)]) -> S
+    where S: Float,
+{
+    let (&end, interior) = match after.split_last() {
+        Some(split) => split,
+        None => return S::zero(),
+    };
+    let (width, diff) = (end.0 - start.0, end.1 - start.1);
+    if width == S::zero() {
+        return S::zero();
+    }
+    // The curve scales a parabola that is added to the straight line between the points.
+    let (rg, gg) = interior.iter().fold((S::zero(), S::zero()), |(rg, gg), &(x, y)| {
+        let t = (x - start.0) / width;
+        let r = y - start.1 - diff * t;
+        let g = diff * t * (S::one() - t);
+        (rg + r * g, gg + g * g)
+    });
+    if gg > S::zero() { rg / gg } else { S::zero() }
+}
+
+
+/// The slope at `sample` of the parabola through it and its neighbouring samples.
+///
+/// At either end, the parabola through the two nearest samples is used instead. Neighbours that
+/// share the `x` of the sample are ignored.
+fn slope<S>(before: &[(S, S)], sample: (S, S), after: &[(S, S)]) -> S
+    where S: Float,
+{
+    let two: S = scalar(2.0);
+    let secant = |a: (S, S), b: (S, S)| (b.1 - a.1) / (b.0 - a.0);
+    let distinct = |p: &&(S, S)| p.0 != sample.0;
+    let maybe_prev = before.last().filter(distinct).cloned();
+    let maybe_next = after.first().filter(distinct).cloned();
+    match (maybe_prev, maybe_next) {
+        (Some(prev), Some(next)) => {
+            let (h1, h2) = (sample.0 - prev.0, next.0 - sample.0);
+            (secant(prev, sample) * h2 + secant(sample, next) * h1) / (h1 + h2)
+        },
+        (None, Some(next)) => match after.get(1).filter(|p| p.0 != next.0) {
+            Some(&far) => {
+                let (h1, h2) = (next.0 - sample.0, far.0 - next.0);
+                (secant(sample, next) * (two * h1 + h2) - secant(next, far) * h1) / (h1 + h2)
+            },
+            None => secant(sample, next),
+        },
+        (Some(prev), None) => match before.len().checked_sub(2).map(|i| before[i]) {
+            Some(far) if far.0 != prev.0 => {
+                let (h1, h2) = (prev.0 - far.0, sample.0 - prev.0);
+                (secant(prev, sample) * (two * h2 + h1) - secant(far, prev) * h2) / (h1 + h2)
+            },
+            _ => secant(prev, sample),
+        },
+        (None, None) => S::zero(),
+    }
+}
+
+
+impl<X, Y> FitPoint for EasePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float + Ease,
+{
+    /// Chooses whichever of a linear segment or each `EaseFunction` fits best.
+    fn fit(before: &[(X, Y)], (x, y): (X, Y), after: &[(X, Y)]) -> Self {
+        let candidates = once(None)
+            .chain(EASE_FUNCTIONS.iter().cloned().map(Some))
+            .map(|maybe_ease_fn| EasePoint::new(x, y, maybe_ease_fn));
+        best_fit(before, after, candidates, |x, y| EasePoint::new(x, y, None))
+    }
+}
+
+
+impl<X, Y> FitPoint for BezierPoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    /// Fits the `curve` by least squares.
+    fn fit(_before: &[(X, Y)], (x, y): (X, Y), after: &[(X, Y)]) -> Self {
+        let curve = bezier_curve((scalar(x), scalar(y)), &scalars(after));
+        BezierPoint::new(x, y, curve)
+    }
+}
+
+
+impl<X, Y> FitPoint for AnyPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float + Ease,
+{
+    /// Chooses whichever of a linear, cosine or least-squares bezier segment or each
+    /// `EaseFunction` fits best.
+    fn fit(before: &[(X, Y)], (x, y): (X, Y), after: &[(X, Y)]) -> Self {
+        let curve = bezier_curve((scalar(x), scalar(y)), &scalars(after));
+        let shapes = vec![SegmentShape::Linear, SegmentShape::Cosine, SegmentShape::Bezier(curve)];
+        let candidates = shapes.into_iter()
+            .chain(EASE_FUNCTIONS.iter().cloned().map(SegmentShape::Ease))
+            .map(|shape| AnyPoint::new(x, y, shape));
+        best_fit(before, after, candidates, |x, y| AnyPoint::new(x, y, SegmentShape::Linear))
+    }
+}
+
+
+impl<X, Y> FitPoint for SplinePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    /// Chooses whichever of a linear segment, a monotone spline or a centripetal Catmull-Rom
+    /// spline fits best.
+    fn fit(before: &[(X, Y)], (x, y): (X, Y), after: &[(X, Y)]) -> Self {
+        let splines = vec![
+            None,
+            Some(Spline::Monotone),
+            Some(Spline::CatmullRom(CatmullRom::Centripetal)),
+        ];
+        let candidates = splines.into_iter()
+            .map(|maybe_spline| SplinePoint::new(x, y, maybe_spline));
+        best_fit(before, after, candidates, |x, y| SplinePoint::new(x, y, None))
+    }
+}
+
+
+impl<X, Y> FitPoint for HermitePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    /// Takes the `tangent` from the slope of the samples surrounding the point.
+    fn fit(before: &[(X, Y)], (x, y): (X, Y), after: &[(X, Y)]) -> Self {
+        let sample = (scalar(x), scalar(y));
+        let tangent = slope(&scalars(before), sample, &scalars(after));
+        HermitePoint::new(x, y, tangent)
+    }
+}
+
+
+impl<X, Y> FitPoint for CubicBezierPoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    /// Takes the slope of both handles from the slope of the samples surrounding the point, with
+    /// each handle reaching a third of the way across its segment.
+    fn fit(before: &[(X, Y)], (x, y): (X, Y), after: &[(X, Y)]) -> Self {
+        let (before, after) = (scalars(before), scalars(after));
+        let sample: (Y::Scalar, Y::Scalar) = (scalar(x), scalar(y));
+        let tangent = slope(&before, sample, &after);
+        let three: Y::Scalar = scalar(3.0);
+        let handle = |width: Y::Scalar| (width / three, width / three * tangent);
+        let zero: Y::Scalar = scalar(0.0);
+        let in_handle = before.first().map_or((zero, zero), |prev| handle(prev.0 - sample.0));
+        let out_handle = after.last().map_or((zero, zero), |next| handle(next.0 - sample.0));
+        CubicBezierPoint::new(x, y, in_handle, out_handle)
+    }
+}
@@ -0,0 +1,71 @@
+use interpolate;
+use interpolation::Spatial;
+use num::{Float, NumCast};
+use point::{Point, PointMut};
+
+
+/// A type whose interpolation follows a cubic Hermite curve, leaving and arriving at each point
+/// with that point's `tangent`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HermitePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    pub x: X,
+    pub y: Y,
+    /// The slope of the curve at this point, i.e. the change in `y` per unit of `x`.
+    pub tangent: Y::Scalar,
+}
+
+
+impl<X, Y> HermitePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    /// Constructor for a HermitePoint.
+    #[inline]
+    pub fn new(x: X, y: Y, tangent: Y::Scalar) -> HermitePoint<X, Y> {
+        HermitePoint {
+            x,
+            y,
+            tangent,
+        }
+    }
+}
+
+
+impl<X, Y> Point for HermitePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    type X = X;
+    type Y = Y;
+
+    #[inline(always)]
+    fn x_to_scalar(x: X) -> Y::Scalar {
+        NumCast::from(x).unwrap()
+    }
+    #[inline(always)]
+    fn x(&self) -> X { self.x }
+    #[inline(always)]
+    fn y(&self) -> Y { self.y }
+    #[inline(always)]
+    fn interpolate(x: X, start: &Self, end: &Self) -> Y {
+        interpolate::hermite(x, start, end, start.tangent, end.tangent)
+    }
+}
+
+
+impl<X, Y> PointMut for HermitePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    #[inline(always)]
+    fn set_x(&mut self, x: X) { self.x = x; }
+    #[inline(always)]
+    fn set_y(&mut self, y: Y) { self.y = y; }
+}
@@ -0,0 +1,189 @@
+use envelope::Envelope;
+use interpolation::{Ease, Spatial};
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
+
+
+/// The scalar type in which the area beneath the envelope `E` is measured.
+pub type Area<'a, E> = <<E as Envelope<'a>>::Y as Spatial>::Scalar;
+
+/// An iterator yielding the area beneath the envelope `E` from some `start` to each following
+/// `step`, i.e. the running integral of the envelope.
+///
+/// The steps land on the same positions as those of `Envelope::steps`, and the first step always
+/// yields zero. Returns `None` the first time a step falls beyond the last point.
+#[derive(Clone)]
+pub struct RunningIntegral<'a, E>
+    where E: Envelope<'a> + 'a,
+          E::Y: Spatial,
+          Area<'a, E>: Float,
+{
+    segments: Segments<'a, E>,
+    start: Area<'a, E>,
+    step: Area<'a, E>,
+    n: u64,
+    /// The position of the last step along with the area up to it.
+    maybe_last: Option<(Area<'a, E>, Area<'a, E>)>,
+    end: Area<'a, E>,
+}
+
+/// Walks forwards through the segments of an envelope, remembering the neighbours of each.
+#[derive(Clone)]
+struct Segments<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    points: E::Points,
+    maybe_prev: Option<&'a E::Point>,
+    left: &'a E::Point,
+    maybe_right: Option<&'a E::Point>,
+}
+
+
+impl<'a, E> Segments<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          Area<'a, E>: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    /// The segments of the given envelope from the one in which `lo` lies, along with the range
+    /// of the points on the x axis.
+    fn new(env: &'a E, lo: Area<'a, E>) -> Option<(Self, Area<'a, E>, Area<'a, E>)> {
+        let mut points = env.points();
+        let first = points.next()?;
+        let last = points.clone().next_back().unwrap_or(first);
+        let range = (E::Point::x_to_scalar(Breakpoint::x(first)),
+                     E::Point::x_to_scalar(Breakpoint::x(last)));
+
+        // With random access to the points, skip straight to the first segment ending on or after
+        // `lo`. `nth` is constant time for slice iterators.
+        let skip = env.points_slice().map_or(0, |slice| {
+            slice[1..].partition_point(|point| E::Point::x_to_scalar(Breakpoint::x(point)) < lo)
+        });
+        let (maybe_prev, left) = match skip {
+            0 => (None, first),
+            1 => (Some(first), points.next()?),
+            _ => (points.nth(skip - 2), points.next()?),
+        };
+        let maybe_right = points.next();
+        let segments = Segments { points, maybe_prev, left, maybe_right };
+        Some((segments, range.0, range.1))
+    }
+
+    /// The area from `lo` to `hi`, moving forwards through the segments as necessary.
+    ///
+    /// Assumes that `lo <= hi` and that neither lies before the current segment.
+    fn integrate(&mut self, mut lo: Area<'a, E>, hi: Area<'a, E>) -> Area<'a, E> {
+        let mut area: Area<'a, E> = scalar(0.0);
+        while let Some(right) = self.maybe_right {
+            let left_x = E::Point::x_to_scalar(Breakpoint::x(self.left));
+            let right_x = E::Point::x_to_scalar(Breakpoint::x(right));
+            let segment_hi = hi.min(right_x);
+            if segment_hi > lo {
+                area = area + self.segment_area(right, lo.max(left_x), segment_hi);
+            }
+            if hi <= right_x {
+                break;
+            }
+            lo = lo.max(right_x);
+            self.maybe_prev = Some(self.left);
+            self.left = right;
+            self.maybe_right = self.points.next();
+        }
+        area
+    }
+
+    /// The area beneath the current segment from `lo` to `hi`.
+    fn segment_area(&self, right: &'a E::Point, lo: Area<'a, E>, hi: Area<'a, E>)
+        -> Area<'a, E>
+    {
+        let left = self.left;
+        let start = (E::Point::x_to_scalar(Breakpoint::x(left)), scalar(Breakpoint::y(left)));
+        let end = (E::Point::x_to_scalar(Breakpoint::x(right)), scalar(Breakpoint::y(right)));
+        match Point::segment_shape(left, right) {
+            Some(shape) => shape.integral(start, end, hi) - shape.integral(start, end, lo),
+            // Without a known shape, even a segment between equal `y`s may bend between them.
+            None => {
+                let (maybe_prev, maybe_next) = (self.maybe_prev, self.points.clone().next());
+                let f = |x| -> Area<'a, E> {
+                    scalar(Breakpoint::interpolate_segment(scalar(x), maybe_prev, left, right,
+                                                           maybe_next))
+                };
+                let mid = f((lo + hi) / scalar(2.0)).abs();
+                let scale = start.1.abs() + end.1.abs() + mid;
+                numeric::integrate(f, lo, hi, Area::<'a, E>::epsilon() * scale * (hi - lo))
+            },
+        }
+    }
+}
+
+
+/// The area beneath the envelope between `x0` and `x1`.
+pub fn integrate<'a, E>(env: &'a E, x0: E::X, x1: E::X) -> Option<Area<'a, E>>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          Area<'a, E>: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    let (x0, x1) = (E::Point::x_to_scalar(x0), E::Point::x_to_scalar(x1));
+    let (lo, hi) = if x0 <= x1 { (x0, x1) } else { (x1, x0) };
+    let (mut segments, first, last) = Segments::new(env, lo)?;
+    if lo < first || hi > last {
+        return None;
+    }
+    let area = segments.integrate(lo, hi);
+    Some(if x0 <= x1 { area } else { -area })
+}
+
+
+impl<'a, E> RunningIntegral<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          Area<'a, E>: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    /// The running integral of the given envelope from `start` at every `step`.
+    ///
+    /// Returns `None` if `start` lies out of range of the points.
+    pub fn new(env: &'a E, start: E::X, step: E::X) -> Option<Self> {
+        let start = E::Point::x_to_scalar(start);
+        let (segments, first, end) = Segments::new(env, start)?;
+        if start < first || start > end {
+            return None;
+        }
+        Some(RunningIntegral {
+            segments,
+            start,
+            step: E::Point::x_to_scalar(step),
+            n: 0,
+            maybe_last: None,
+            end,
+        })
+    }
+}
+
+impl<'a, E> Iterator for RunningIntegral<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          Area<'a, E>: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    type Item = Area<'a, E>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.start + self.step * scalar(self.n);
+        if x > self.end {
+            return None;
+        }
+        self.n += 1;
+        let area = match self.maybe_last {
+            Some((last_x, last_area)) => last_area + self.segments.integrate(last_x, x),
+            None => scalar(0.0),
+        };
+        self.maybe_last = Some((x, area));
+        Some(area)
+    }
+}
@@ -1,7 +1,8 @@
 
 use interpolation::{Ease, EaseFunction, Spatial};
-use num::NumCast;
-use point::Point;
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
 use std;
 
 
@@ -39,6 +40,53 @@ impl<T> Scalar for T
         + std::ops::Div<Output=T> {}
 
 
+/// The position within a stepped segment at which `y` jumps from the start point's value to the
+/// end point's value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Jump {
+    /// Hold the start point's `y` until the end point is reached.
+    End,
+    /// Jump to the end point's `y` immediately after the start point.
+    Start,
+    /// Jump at the given fraction of the segment, from `0.0` (the start) to `1.0` (the end).
+    At(f64),
+}
+
+impl Jump {
+    /// The fraction of the segment at which the jump occurs.
+    #[inline]
+    pub fn fraction(&self) -> f64 {
+        match *self {
+            Jump::End => 1.0,
+            Jump::Start => 0.0,
+            Jump::At(fraction) => fraction.clamp(0.0, 1.0),
+        }
+    }
+}
+
+
+/// Hold the start point's `y` until the given `Jump`, after which the end point's `y` is returned.
+///
+/// Unlike the other interpolators, `y` need not be `Spatial`, so it may be a `bool`, an integer
+/// or an enum.
+#[inline]
+pub fn step<P>(x: P::X, start: &P, end: &P, jump: Jump) -> P::Y
+    where P: Breakpoint,
+          P::X: PartialOrd + NumCast,
+{
+    let is_before_jump = match jump {
+        Jump::End => x < end.x(),
+        Jump::Start => x <= start.x(),
+        Jump::At(_) => {
+            let start_x: f64 = scalar(start.x());
+            let end_x: f64 = scalar(end.x());
+            x == start.x() || scalar::<f64, _>(x) < start_x + (end_x - start_x) * jump.fraction()
+        },
+    };
+    if is_before_jump { start.y() } else { end.y() }
+}
+
+
 /// Interpolate linearly between the start and end points.
 #[inline]
 pub fn linear<P>(x: P::X, start: &P, end: &P) -> P::Y
@@ -77,6 +125,54 @@ pub fn ease<P>(x: P::X, start: &P, end: &P, ease_fn: EaseFunction) -> P::Y
 }
 
 
+/// Interpolate between the start and end points along an exponential curve.
+///
+/// `k` sets the steepness of the curve. A positive `k` starts slowly and finishes quickly, a
+/// negative `k` does the opposite and a `k` of zero produces a straight line.
+#[inline]
+pub fn exponential<P>(x: P::X, start: &P, end: &P, k: <P::Y as Spatial>::Scalar) -> P::Y
+    where P: Point,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    maybe_exact_point(&x, start, end).unwrap_or_else(|| {
+        let x = P::x_to_scalar(x);
+        let start_x = P::x_to_scalar(start.x());
+        let end_x = P::x_to_scalar(end.x());
+        let scalar = (x - start_x) / (end_x - start_x);
+        let curved_scalar = if k.abs() <= Float::epsilon() {
+            scalar
+        } else {
+            (k * scalar).exp_m1() / k.exp_m1()
+        };
+        let difference = end.y().sub(&start.y());
+        let interpolated_difference = difference.scale(&curved_scalar);
+        start.y().add(&interpolated_difference)
+    })
+}
+
+
+/// Interpolate between the start and end points along half a period of a cosine wave, easing in
+/// and out of each point.
+#[inline]
+pub fn cosine<P>(x: P::X, start: &P, end: &P) -> P::Y
+    where P: Point,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    maybe_exact_point(&x, start, end).unwrap_or_else(|| {
+        let x = P::x_to_scalar(x);
+        let start_x = P::x_to_scalar(start.x());
+        let end_x = P::x_to_scalar(end.x());
+        let pi: <P::Y as Spatial>::Scalar = scalar(std::f64::consts::PI);
+        let (one, two): (<P::Y as Spatial>::Scalar, _) = (scalar(1.0), scalar(2.0));
+        let scalar = (x - start_x) / (end_x - start_x);
+        let curved_scalar = (one - (scalar * pi).cos()) / two;
+        let difference = end.y().sub(&start.y());
+        let interpolated_difference = difference.scale(&curved_scalar);
+        start.y().add(&interpolated_difference)
+    })
+}
+
+
 /// Get bezier point for bezier curve.
 #[inline]
 fn bezier_pt<T>(n1: T, n2: T, perc: T) -> T
@@ -117,3 +213,261 @@ pub fn bezier<P>(x: P::X, start: &P, end: &P, curve: <P::Y as Spatial>::Scalar)
         start.y().add(&y)
     })
 }
+
+
+/// The parameterization of a Catmull-Rom spline.
+///
+/// This determines how the spacing of the spline's knots relates to the distance between points.
+/// `Uniform` is the classic Catmull-Rom spline. `Centripetal` never forms cusps or loops within a
+/// segment, which makes it the safest choice for unevenly spaced points.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CatmullRom {
+    Uniform,
+    Centripetal,
+    Chordal,
+}
+
+impl CatmullRom {
+    /// The exponent applied to the distance between points when spacing the knots.
+    ///
+    /// This is `0`, `0.5` and `1` for `Uniform`, `Centripetal` and `Chordal` respectively.
+    #[inline]
+    pub fn alpha<S>(&self) -> S
+        where S: Float,
+    {
+        match *self {
+            CatmullRom::Uniform => S::zero(),
+            CatmullRom::Centripetal => scalar(0.5),
+            CatmullRom::Chordal => S::one(),
+        }
+    }
+}
+
+
+/// Interpolate between the start and end points along a Catmull-Rom spline.
+///
+/// `prev` and `next` are the points on either side of the segment and determine the tangents at
+/// `start` and `end`. A missing neighbour is mirrored from the segment, as if the curve continued
+/// in a straight line.
+///
+/// The spline is evaluated in both dimensions, so the curve parameter is solved for the given `x`
+/// by bisection.
+pub fn catmull_rom<P>(x: P::X,
+                      prev: Option<&P>,
+                      start: &P,
+                      end: &P,
+                      next: Option<&P>,
+                      catmull_rom: CatmullRom) -> P::Y
+    where P: Point,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    // Unlike the other interpolators, equal y values do not imply a flat segment.
+    if start.x() == x {
+        return start.y();
+    } else if end.x() == x {
+        return end.y();
+    }
+
+    let two: <P::Y as Spatial>::Scalar = scalar(2.0);
+    let xy = |p: &P| (P::x_to_scalar(p.x()), scalar(p.y()));
+    let p1 = xy(start);
+    let p2 = xy(end);
+    let p0 = prev.map(&xy).unwrap_or((p1.0 * two - p2.0, p1.1 * two - p2.1));
+    let p3 = next.map(&xy).unwrap_or((p2.0 * two - p1.0, p2.1 * two - p1.1));
+    scalar(catmull_rom_y([p0, p1, p2, p3], P::x_to_scalar(x), catmull_rom.alpha()))
+}
+
+/// Solve the centre segment of the Catmull-Rom spline through the given points for `y` at `x`.
+fn catmull_rom_y<S>(points: [(S, S); 4], x: S, alpha: S) -> S
+    where S: Float,
+{
+    let [p0, p1, p2, p3] = points;
+
+    // The distance between each pair of knots.
+    let interval = |a: (S, S), b: (S, S)| {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let interval = (dx * dx + dy * dy).sqrt().powf(alpha);
+        // Coincident points would otherwise collapse the interval.
+        if interval > S::epsilon() { interval } else { S::one() }
+    };
+    let t0 = S::zero();
+    let t1 = t0 + interval(p0, p1);
+    let t2 = t1 + interval(p1, p2);
+    let t3 = t2 + interval(p2, p3);
+
+    // Barry and Goldman's pyramidal formulation.
+    let lerp = |a: (S, S), b: (S, S), ta: S, tb: S, t: S| {
+        let w = (t - ta) / (tb - ta);
+        (a.0 + (b.0 - a.0) * w, a.1 + (b.1 - a.1) * w)
+    };
+    let curve = |t| {
+        let a1 = lerp(p0, p1, t0, t1, t);
+        let a2 = lerp(p1, p2, t1, t2, t);
+        let a3 = lerp(p2, p3, t2, t3, t);
+        let b1 = lerp(a1, a2, t0, t2, t);
+        let b2 = lerp(a2, a3, t1, t3, t);
+        lerp(b1, b2, t1, t2, t)
+    };
+
+    let t = numeric::bisect(&mut |t| curve(t).0 - x, t1, t2, p1.0 - x);
+    curve(t).1
+}
+
+
+/// Interpolate between the start and end points along a cubic Hermite curve.
+///
+/// `start_tangent` and `end_tangent` are the slopes (the change in y per unit of x) with which the
+/// curve leaves `start` and arrives at `end`.
+#[inline]
+pub fn hermite<P>(x: P::X,
+                  start: &P,
+                  end: &P,
+                  start_tangent: <P::Y as Spatial>::Scalar,
+                  end_tangent: <P::Y as Spatial>::Scalar) -> P::Y
+    where P: Point,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    if start.x() == x {
+        return start.y();
+    } else if end.x() == x {
+        return end.y();
+    }
+
+    let start = (P::x_to_scalar(start.x()), scalar(start.y()));
+    let end = (P::x_to_scalar(end.x()), scalar(end.y()));
+    scalar(hermite_y(start, end, start_tangent, end_tangent, P::x_to_scalar(x)))
+}
+
+/// Evaluate the cubic Hermite curve between `start` and `end` at `x`.
+fn hermite_y<S>(start: (S, S), end: (S, S), start_tangent: S, end_tangent: S, x: S) -> S
+    where S: Float,
+{
+    let duration = end.0 - start.0;
+    let s = (x - start.0) / duration;
+    let (s2, s3) = (s * s, s * s * s);
+    let (two, three): (S, S) = (scalar(2.0), scalar(3.0));
+
+    // The hermite basis functions.
+    let h00 = two * s3 - three * s2 + S::one();
+    let h10 = s3 - two * s2 + s;
+    let h01 = three * s2 - two * s3;
+    let h11 = s3 - s2;
+
+    h00 * start.1 + h10 * duration * start_tangent + h01 * end.1 + h11 * duration * end_tangent
+}
+
+
+/// Interpolate between the start and end points along a monotone cubic curve.
+///
+/// The curve is a cubic Hermite curve whose tangents are chosen with the Fritsch-Carlson method
+/// (using the weighted harmonic mean of Fritsch and Butland, as in PCHIP), so it never overshoots:
+/// y always stays between `start.y()` and `end.y()`. A local extremum or a flat run of points
+/// produces a flat tangent.
+///
+/// `prev` and `next` are the points on either side of the segment. The tangent at a point without
+/// a neighbour follows the segment.
+pub fn monotone_cubic<P>(x: P::X, prev: Option<&P>, start: &P, end: &P, next: Option<&P>) -> P::Y
+    where P: Point,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    maybe_exact_point(&x, start, end).unwrap_or_else(|| {
+        let xy = |p: &P| (P::x_to_scalar(p.x()), scalar(p.y()));
+        let start = xy(start);
+        let end = xy(end);
+        let (start_tangent, end_tangent) =
+            monotone_tangents(prev.map(&xy), start, end, next.map(&xy));
+        scalar(hermite_y(start, end, start_tangent, end_tangent, P::x_to_scalar(x)))
+    })
+}
+
+/// The tangents at `start` and `end` for a monotone cubic segment.
+fn monotone_tangents<S>(prev: Option<(S, S)>, start: (S, S), end: (S, S), next: Option<(S, S)>)
+    -> (S, S)
+    where S: Float,
+{
+    let secant = |a: (S, S), b: (S, S)| (b.0 - a.0, (b.1 - a.1) / (b.0 - a.0));
+    let (h, d) = secant(start, end);
+
+    // The weighted harmonic mean of the secants either side of a point, or a flat tangent if the
+    // point is a local extremum.
+    let tangent = |(h0, d0): (S, S), (h1, d1): (S, S)| {
+        if d0 * d1 <= S::zero() {
+            return S::zero();
+        }
+        let w0 = h1 + h1 + h0;
+        let w1 = h1 + h0 + h0;
+        (w0 + w1) / (w0 / d0 + w1 / d1)
+    };
+
+    // Neighbours that are missing or vertical have no meaningful secant.
+    let start_tangent = match prev {
+        Some(prev) if prev.0 < start.0 => tangent(secant(prev, start), (h, d)),
+        _ => d,
+    };
+    let end_tangent = match next {
+        Some(next) if end.0 < next.0 => tangent((h, d), secant(end, next)),
+        _ => d,
+    };
+    (start_tangent, end_tangent)
+}
+
+
+/// Interpolate between the start and end points along a cubic bezier curve.
+///
+/// `start_handle` and `end_handle` are the offsets of the curve's two inner control points from
+/// `start` and `end` respectively, in both x and y. The x of each handle is clamped to the range
+/// of the segment so that the curve never doubles back on itself in x.
+///
+/// The curve parameter is solved for the given `x` with Newton's method, falling back to
+/// bisection, to within a tolerance of a few ULPs of `x`.
+pub fn cubic_bezier<P>(x: P::X,
+                       start: &P,
+                       end: &P,
+                       start_handle: (<P::Y as Spatial>::Scalar, <P::Y as Spatial>::Scalar),
+                       end_handle: (<P::Y as Spatial>::Scalar, <P::Y as Spatial>::Scalar)) -> P::Y
+    where P: Point,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    // The handles may bend the curve even when both y values are the same.
+    if start.x() == x {
+        return start.y();
+    } else if end.x() == x {
+        return end.y();
+    }
+
+    let start = (P::x_to_scalar(start.x()), scalar(start.y()));
+    let end = (P::x_to_scalar(end.x()), scalar(end.y()));
+    let (lo, hi) = if start.0 < end.0 { (start.0, end.0) } else { (end.0, start.0) };
+    let clamp = |x: <P::Y as Spatial>::Scalar| x.max(lo).min(hi);
+    let c1 = (clamp(start.0 + start_handle.0), start.1 + start_handle.1);
+    let c2 = (clamp(end.0 + end_handle.0), end.1 + end_handle.1);
+    scalar(cubic_bezier_y([start, c1, c2, end], P::x_to_scalar(x)))
+}
+
+/// Solve the cubic bezier curve with the given control points for `y` at `x`.
+fn cubic_bezier_y<S>(points: [(S, S); 4], x: S) -> S
+    where S: Float,
+{
+    let [p0, p1, p2, p3] = points;
+    let three: S = scalar(3.0);
+
+    // The power basis coefficients of each dimension.
+    let coefficients = |a: S, b: S, c: S, d: S| {
+        (d - a + three * (b - c), three * (a - b - b + c), three * (b - a), a)
+    };
+    let (xa, xb, xc, xd) = coefficients(p0.0, p1.0, p2.0, p3.0);
+    let (ya, yb, yc, yd) = coefficients(p0.1, p1.1, p2.1, p3.1);
+
+    let x_and_slope = |t: S| {
+        let x_at_t = ((xa * t + xb) * t + xc) * t + xd;
+        let slope = (three * xa * t + xb + xb) * t + xc;
+        (x_at_t - x, slope)
+    };
+    let tolerance = S::epsilon() * (p0.0.abs().max(p3.0.abs()).max(S::one())) * scalar(4.0);
+    let t = numeric::newton_bisect(x_and_slope, S::zero(), S::one(), tolerance);
+    ((ya * t + yb) * t + yc) * t + yd
+}
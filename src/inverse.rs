@@ -0,0 +1,240 @@
+use envelope::Envelope;
+use interpolation::{Ease, EaseFunction, Spatial};
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
+use shape::{self, SegmentShape};
+use std;
+
+
+/// The error returned when an envelope cannot be inverted by `Envelope::x_at_y`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InverseError {
+    /// The envelope has no points.
+    Empty,
+    /// The envelope changes direction within the segment beginning at the point with the given
+    /// index, so some `y`s may be produced by more than one `x`.
+    NotMonotonic {
+        idx: usize,
+    },
+    /// The `y` lies outside the range of the envelope.
+    OutOfRange,
+}
+
+
+/// A monotonic envelope `E` whose monotonicity has been checked once, allowing for fast `x_at_y`
+/// queries over large envelopes.
+///
+/// The inverse must be rebuilt if the envelope's points change.
+#[derive(Clone)]
+pub struct Inverse<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    points: Vec<&'a E::Point>,
+    is_rising: bool,
+}
+
+
+/// The `x` at which a monotonic envelope produces the given `y`.
+#[inline]
+pub fn x_at_y<'a, E>(env: &'a E, y: E::Y) -> Result<E::X, InverseError>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    Inverse::new(env)?.x_at_y(y)
+}
+
+
+impl<'a, E> Inverse<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    /// Check that every segment of the given envelope moves in the same direction without turning
+    /// back on itself.
+    ///
+    /// Returns an `InverseError` if the envelope has no points or if it is not monotonic.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    pub fn new(env: &'a E) -> Result<Self, InverseError> {
+        let points: Vec<&'a E::Point> = env.points().collect();
+        let (first, last) = match (points.first(), points.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return Err(InverseError::Empty),
+        };
+        let is_rising = y_of(first) <= y_of(last);
+        for (idx, pair) in points.windows(2).enumerate() {
+            let (left, right) = (pair[0], pair[1]);
+            let (y0, y1) = (y_of(left), y_of(right));
+            let maybe_prev = idx.checked_sub(1).map(|i| points[i]);
+            let maybe_next = points.get(idx + 2).cloned();
+            if (y0 != y1 && (y0 < y1) != is_rising)
+                || !is_monotonic(maybe_prev, left, right, maybe_next)
+            {
+                return Err(InverseError::NotMonotonic { idx });
+            }
+        }
+        Ok(Inverse { points, is_rising })
+    }
+
+    /// The `x` at which the envelope produces the given `y`.
+    ///
+    /// The result is the same as that of `Envelope::x_at_y`, but only the segment holding the `y`
+    /// is evaluated, and it is found via binary search.
+    ///
+    /// Returns `InverseError::OutOfRange` if the `y` lies outside of the range of the envelope.
+    pub fn x_at_y(&self, y: E::Y) -> Result<E::X, InverseError> {
+        let points = &self.points;
+        let (first, last) = (points[0], points[points.len() - 1]);
+        let y: <E::Y as Spatial>::Scalar = scalar(y);
+        let (min, max) = if self.is_rising {
+            (y_of(first), y_of(last))
+        } else {
+            (y_of(last), y_of(first))
+        };
+        if y < min || y > max {
+            return Err(InverseError::OutOfRange);
+        }
+
+        // The `y`s of the points are in order, so the first point to reach `y` may be found via
+        // binary search.
+        let is_rising = self.is_rising;
+        let idx = points.partition_point(|&p| if is_rising { y_of(p) < y } else { y_of(p) > y });
+        let right = *points.get(idx).ok_or(InverseError::OutOfRange)?;
+        if idx == 0 || y_of(right) == y {
+            return Ok(scalar(x_of(right)));
+        }
+        let maybe_prev = idx.checked_sub(2).map(|i| points[i]);
+        let maybe_next = points.get(idx + 1).cloned();
+        Ok(scalar(segment_x_at_y(maybe_prev, points[idx - 1], right, maybe_next, y)))
+    }
+}
+
+
+/// The `y` of the given point as a scalar.
+#[inline]
+fn y_of<P>(point: &P) -> <P::Y as Spatial>::Scalar
+    where P: Point,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    scalar(Breakpoint::y(point))
+}
+
+/// The `x` of the given point as a scalar.
+#[inline]
+fn x_of<P>(point: &P) -> <P::Y as Spatial>::Scalar
+    where P: Point,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    P::x_to_scalar(Breakpoint::x(point))
+}
+
+
+/// Whether the segment from `left` to `right` never changes direction.
+fn is_monotonic<P>(maybe_prev: Option<&P>, left: &P, right: &P, maybe_next: Option<&P>) -> bool
+    where P: Point,
+          P::X: NumCast,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float + Ease,
+{
+    let (x0, y0) = (P::x_to_scalar(Breakpoint::x(left)), scalar(Breakpoint::y(left)));
+    let (x1, y1): (_, <P::Y as Spatial>::Scalar) =
+        (P::x_to_scalar(Breakpoint::x(right)), scalar(Breakpoint::y(right)));
+    let diff = y1 - y0;
+    let zero = scalar(0.0);
+    if x0 == x1 {
+        return true;
+    }
+    match Point::segment_shape(left, right) {
+        // Every known shape is flat between equal `y`s.
+        Some(_) if diff == zero => true,
+        Some(SegmentShape::Bezier(curve)) => {
+            // The slope `2*a*t + b` is linear in `t`, so only its ends need to be checked.
+            let (a, b) = shape::bezier_coefficients(diff, curve);
+            b * diff >= zero && (a + a + b) * diff >= zero
+        },
+        Some(SegmentShape::Ease(ease_fn)) => match ease_fn {
+            // These overshoot, oscillate about or bounce off their ends. `SineIn` and `SineOut`
+            // each trace a whole period of a sine wave.
+            EaseFunction::SineIn | EaseFunction::SineOut |
+            EaseFunction::BackIn | EaseFunction::BackOut | EaseFunction::BackInOut |
+            EaseFunction::ElasticIn | EaseFunction::ElasticOut | EaseFunction::ElasticInOut |
+            EaseFunction::BounceIn | EaseFunction::BounceOut | EaseFunction::BounceInOut => false,
+            _ => true,
+        },
+        Some(_) => true,
+        // Without a known shape, check that the slope never opposes the direction of the segment
+        // at any of the points at which `numeric::roots` would look for a crossing.
+        None => {
+            let f = |x| -> <P::Y as Spatial>::Scalar {
+                scalar(Breakpoint::interpolate_segment(scalar(x), maybe_prev, left, right,
+                                                       maybe_next))
+            };
+            let width = x1 - x0;
+            let n: <P::Y as Spatial>::Scalar = scalar(numeric::SUBDIVISIONS);
+            let epsilon: <P::Y as Spatial>::Scalar = Float::epsilon();
+            let tolerance = epsilon.sqrt() * (y0.abs() + y1.abs()) / width;
+            (0..numeric::SUBDIVISIONS + 1).all(|i| {
+                let x = x0 + width * scalar(i) / n;
+                let slope = numeric::derivative(&f, x, x0, x1);
+                if diff == zero {
+                    slope.abs() <= tolerance
+                } else {
+                    slope * diff.signum() >= -tolerance
+                }
+            })
+        },
+    }
+}
+
+
+/// The first `x` at which the segment from `left` to `right` produces `y`, where `y` lies between
+/// the `y`s of its ends and the segment is monotonic.
+fn segment_x_at_y<P>(maybe_prev: Option<&P>, left: &P, right: &P, maybe_next: Option<&P>,
+                     y: <P::Y as Spatial>::Scalar) -> <P::Y as Spatial>::Scalar
+    where P: Point,
+          P::X: NumCast,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float + Ease,
+{
+    let start = (P::x_to_scalar(Breakpoint::x(left)), scalar(Breakpoint::y(left)));
+    let end = (P::x_to_scalar(Breakpoint::x(right)), scalar(Breakpoint::y(right)));
+    match Point::segment_shape(left, right) {
+        Some(shape) => {
+            let mut xs = Vec::with_capacity(2);
+            shape.xs_at_y(start, end, y, &mut xs);
+            xs.first().cloned().unwrap_or(start.0)
+        },
+        None => if start.0 == end.0 {
+            start.0
+        } else {
+            let mut f = |x| -> <P::Y as Spatial>::Scalar {
+                let y_at_x: <P::Y as Spatial>::Scalar =
+                    scalar(Breakpoint::interpolate_segment(scalar(x), maybe_prev, left, right,
+                                                           maybe_next));
+                y_at_x - y
+            };
+            numeric::bisect(&mut f, start.0, end.0, start.1 - y)
+        },
+    }
+}
+
+
+impl std::fmt::Display for InverseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            InverseError::Empty => write!(f, "the envelope has no points"),
+            InverseError::NotMonotonic { idx } =>
+                write!(f, "the envelope changes direction within the segment at index {}", idx),
+            InverseError::OutOfRange => write!(f, "the y lies outside the range of the envelope"),
+        }
+    }
+}
+
+impl std::error::Error for InverseError {}
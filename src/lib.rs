@@ -1,16 +1,59 @@
 pub extern crate interpolation;
 extern crate num;
 
+pub use adsr::{Adsr, AdsrStage, Ramp};
+pub use any_point::AnyPoint;
+pub use automation::{AutomationMode, AutomationRecorder};
 pub use bezier_point::BezierPoint;
+pub use containers::{ArrayEnvelope, ArrayEnvelopeError, CapacityError, SliceEnvelope, UnsortedError,
+                     VecEnvelope};
+pub use cubic_bezier_point::CubicBezierPoint;
+pub use derivative::{Derivative, DerivativeSteps};
 pub use ease_point::EasePoint;
-pub use envelope::{Envelope, Steps};
-pub use point::Point;
+pub use envelope::{ClockSteps, Cursor, Envelope, SampleSteps, Steps, XsAtY};
+pub use envelope_mut::EnvelopeMut;
+pub use extrapolation::{Extrapolated, Extrapolation};
+pub use fit::{Fit, FitPoint};
+pub use hermite_point::HermitePoint;
+pub use integral::RunningIntegral;
+pub use inverse::{Inverse, InverseError};
+pub use looping::{Loop, LoopMode, LoopPlayer, LoopingEnvelope};
+pub use player::{Player, Retrigger};
+pub use point::{Breakpoint, Point, PointMut};
+pub use range::{Bounds, RangeIndex};
+pub use shape::SegmentShape;
+pub use spline_point::{Spline, SplinePoint};
+pub use step_point::StepPoint;
+pub use tempo::{BeatSteps, TempoMap};
+pub use time::{SampleClock, Time, TimeUnit};
 
+mod adsr;
+mod any_point;
+mod automation;
 mod bezier_point;
+mod containers;
+mod cubic_bezier_point;
+mod derivative;
 mod ease_point;
 mod envelope;
+mod envelope_mut;
+mod extrapolation;
+mod fit;
+mod hermite_point;
+mod integral;
+mod inverse;
 pub mod interpolate;
+mod looping;
+mod numeric;
+mod player;
 mod point;
+mod range;
+mod shape;
+mod simplify;
+mod spline_point;
+mod step_point;
+mod tempo;
+mod time;
 
 #[cfg(feature="serde_serialization")]
 mod serde;
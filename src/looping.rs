@@ -0,0 +1,250 @@
+use envelope::{Cursor, Envelope};
+use interpolation::Spatial;
+use num::{Float, NumCast};
+use numeric::scalar;
+use std::ops::{Add, Sub};
+
+
+/// The direction in which a `Loop` is repeated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LoopMode {
+    /// Wrap from the end of the loop back to its start.
+    Forward,
+    /// Alternate between playing the loop forwards and backwards.
+    PingPong,
+}
+
+/// A region of an envelope that is repeated while the gate is held.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Loop<X> {
+    pub start: X,
+    pub end: X,
+    pub mode: LoopMode,
+    /// The length over which the end of a `Forward` loop is crossfaded with the part of the
+    /// envelope leading up to its start, so that the wrap is seamless.
+    pub maybe_crossfade: Option<X>,
+}
+
+/// An `Envelope` with sustain, loop and release markers, as used by samplers and multi-segment
+/// modulators.
+///
+/// Playback is driven by a `LoopPlayer` (see `LoopingEnvelope::play`), which tracks the gate:
+///
+/// - While the gate is held, playback repeats the `maybe_loop` region once it is reached. Without
+///   a loop, playback stops at the `maybe_sustain` point instead.
+/// - When the gate closes, playback leaves the loop and jumps forward to the `maybe_release`
+///   point if it has not already passed it. Without a release point, playback continues from
+///   wherever it is.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LoopingEnvelope<E, X> {
+    /// The envelope being played.
+    pub env: E,
+    /// The `x` at which playback holds while the gate is held, if there is no loop.
+    pub maybe_sustain: Option<X>,
+    /// The region repeated while the gate is held.
+    pub maybe_loop: Option<Loop<X>>,
+    /// The `x` at which the release begins once the gate closes.
+    pub maybe_release: Option<X>,
+}
+
+/// Plays a `LoopingEnvelope` one `step` at a time while tracking its gate.
+///
+/// Each time a loop wraps around, the distance by which the step overshot the end of the loop is
+/// carried over to the start, so the spacing between steps is never disturbed. A `PingPong` loop
+/// is therefore always continuous, while a `Forward` loop is continuous as long as the envelope
+/// has the same `y` at both ends of the loop or the loop has a crossfade.
+///
+/// Over the crossfade at the end of a `Forward` loop, each `y` is blended with the `y` the same
+/// distance before the start of the loop, reaching the `y` at the start of the loop by the end.
+/// Where the crossfade reaches before the first point, the `y` at the start of the loop is used.
+#[derive(Clone)]
+pub struct LoopPlayer<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    looping: &'a LoopingEnvelope<E, E::X>,
+    cursor: Cursor<'a, E>,
+    x: E::X,
+    step: E::X,
+    gate: bool,
+    /// Whether a `PingPong` loop is currently playing backwards.
+    reversed: bool,
+}
+
+
+impl<E, X> LoopingEnvelope<E, X> {
+    /// Wrap the given envelope without any markers.
+    #[inline]
+    pub fn new(env: E) -> Self {
+        LoopingEnvelope {
+            env,
+            maybe_sustain: None,
+            maybe_loop: None,
+            maybe_release: None,
+        }
+    }
+
+    /// Start playing the envelope from `start` with the gate open, moving by `step` each time.
+    #[inline]
+    pub fn play<'a>(&'a self, start: X, step: X) -> LoopPlayer<'a, E>
+        where E: Envelope<'a, X=X>,
+    {
+        LoopPlayer {
+            looping: self,
+            cursor: self.env.cursor(),
+            x: start,
+            step,
+            gate: true,
+            reversed: false,
+        }
+    }
+}
+
+
+impl<'a, E> LoopPlayer<'a, E>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X>,
+{
+    /// The position of the next step.
+    #[inline]
+    pub fn x(&self) -> E::X {
+        self.x.clone()
+    }
+
+    /// Whether or not the gate is open.
+    #[inline]
+    pub fn is_gate_on(&self) -> bool {
+        self.gate
+    }
+
+    /// Open the gate, so that the loop (or sustain point) holds playback once again if it has
+    /// not yet been passed.
+    #[inline]
+    pub fn gate_on(&mut self) {
+        self.gate = true;
+    }
+
+    /// Close the gate, leaving any loop and jumping forward to the release point if there is one.
+    pub fn gate_off(&mut self) {
+        if !self.gate {
+            return;
+        }
+        self.gate = false;
+        self.reversed = false;
+        if let Some(ref release) = self.looping.maybe_release {
+            if self.x < *release {
+                self.x = release.clone();
+            }
+        }
+    }
+
+    /// This is useful when the step size must change between steps.
+    #[inline]
+    pub fn set_step(&mut self, step: E::X) {
+        self.step = step;
+    }
+
+    /// Move `x` by one step, honouring the loop and sustain markers while the gate is open.
+    fn advance(&mut self) {
+        let x = self.x.clone();
+        let step = self.step.clone();
+        if !self.gate {
+            self.x = x + step;
+            return;
+        }
+
+        if let Some(Loop { ref start, ref end, mode, .. }) = self.looping.maybe_loop {
+            let was_inside = x >= *start && x <= *end;
+            if self.reversed {
+                let next = x - step;
+                self.x = if next < *start {
+                    self.reversed = false;
+                    start.clone() + (start.clone() - next)
+                } else {
+                    next
+                };
+            } else {
+                let next = x.clone() + step;
+                self.x = if x <= *end && next > *end {
+                    match mode {
+                        _ if start >= end => start.clone(),
+                        LoopMode::Forward => start.clone() + (next - end.clone()),
+                        LoopMode::PingPong => {
+                            self.reversed = true;
+                            end.clone() - (next - end.clone())
+                        },
+                    }
+                } else {
+                    next
+                };
+            }
+            // A step larger than the loop itself may still overshoot, in which case the loop's
+            // edge is the best approximation.
+            if was_inside {
+                if self.x < *start {
+                    self.x = start.clone();
+                } else if self.x > *end {
+                    self.x = end.clone();
+                }
+            }
+            return;
+        }
+
+        let next = x.clone() + step;
+        self.x = match self.looping.maybe_sustain {
+            Some(ref sustain) if x <= *sustain && next > *sustain => sustain.clone(),
+            _ => next,
+        };
+    }
+}
+
+impl<'a, E> LoopPlayer<'a, E>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X> + NumCast,
+          E::Y: PartialEq + Spatial,
+          <E::Y as Spatial>::Scalar: Float,
+{
+    /// Crossfade the `y` at the current position with the `y` leading up to the start of the loop
+    /// if the position lies within the crossfade of a `Forward` loop.
+    fn crossfade(&self, y: E::Y) -> E::Y {
+        let (start, end, len) = match self.looping.maybe_loop {
+            Some(Loop { ref start, ref end, mode, maybe_crossfade: Some(ref len) })
+                if mode == LoopMode::Forward && self.gate && start < end =>
+                (start.clone(), end.clone(), len.clone()),
+            _ => return y,
+        };
+        let loop_len = end.clone() - start.clone();
+        let len = if len > loop_len { loop_len } else { len };
+        let fade_start = end.clone() - len.clone();
+        let x = self.x.clone();
+        if x <= fade_start || x > end {
+            return y;
+        }
+        let env = &self.looping.env;
+        let before = match env.y(start.clone() - (end - x.clone())).or_else(|| env.y(start)) {
+            Some(before) => before,
+            None => return y,
+        };
+        let weight = scalar::<<E::Y as Spatial>::Scalar, _>(x - fade_start) / scalar(len);
+        y.add(&before.sub(&y).scale(&weight))
+    }
+}
+
+impl<'a, E> Iterator for LoopPlayer<'a, E>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X> + NumCast,
+          E::Y: PartialEq + Spatial,
+          <E::Y as Spatial>::Scalar: Float,
+{
+    type Item = E::Y;
+    /// Yields `y` at the current position before moving forward by one step.
+    ///
+    /// Returns `None` once playback falls out of range of the envelope's points.
+    #[inline]
+    fn next(&mut self) -> Option<E::Y> {
+        let y = self.cursor.y_at(self.x.clone()).map(|y| self.crossfade(y));
+        if y.is_some() {
+            self.advance();
+        }
+        y
+    }
+}
@@ -0,0 +1,227 @@
+//! Numeric methods for segments whose curves have no convenient analytic solution.
+
+use num::{Float, NumCast};
+
+
+/// The number of sub-intervals into which a segment is divided when searching for roots.
+///
+/// Two roots lying within the same sub-interval may be missed, so this bounds the "wiggliness" of
+/// the curves that can be solved reliably. It is more than enough for every `EaseFunction`.
+pub const SUBDIVISIONS: usize = 64;
+
+/// The maximum number of bisection steps, enough to reach the precision of an `f64`.
+const MAX_BISECTIONS: usize = 128;
+
+/// The number of sub-intervals into which a segment is first divided when integrating.
+const INTEGRATION_SUBDIVISIONS: usize = 8;
+
+/// The maximum number of times an interval may be halved when integrating.
+const MAX_INTEGRATION_DEPTH: usize = 32;
+
+
+/// Convert a primitive to the scalar type `S`.
+#[inline]
+pub fn scalar<S, T>(n: T) -> S
+    where S: NumCast,
+          T: NumCast,
+{
+    NumCast::from(n).unwrap()
+}
+
+
+/// Find the root of `f` between `lo` and `hi`, where `f_lo` (the value of `f(lo)`) and `f(hi)`
+/// have opposite signs.
+///
+/// Bisects until the interval can no longer be divided, so the error is bounded by the precision
+/// of `S`.
+pub fn bisect<S, F>(f: &mut F, mut lo: S, mut hi: S, mut f_lo: S) -> S
+    where S: Float,
+          F: FnMut(S) -> S,
+{
+    let two: S = scalar(2.0);
+    for _ in 0..MAX_BISECTIONS {
+        let mid = lo + (hi - lo) / two;
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        let f_mid = f(mid);
+        if f_mid == S::zero() {
+            return mid;
+        }
+        if (f_mid < S::zero()) == (f_lo < S::zero()) {
+            lo = mid;
+            f_lo = f_mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo + (hi - lo) / two
+}
+
+
+/// Find the root of `f` between `lo` and `hi` using Newton's method, where `f_df` returns both
+/// `f(x)` and its derivative and `f(lo)` and `f(hi)` have opposite signs.
+///
+/// Each Newton step that would leave the bracketing interval (or that has no slope to follow) is
+/// replaced by a bisection, so convergence is guaranteed. Returns once `f(x)` lies within
+/// `tolerance` of zero, or once the interval can no longer be divided.
+pub fn newton_bisect<S, F>(mut f_df: F, mut lo: S, mut hi: S, tolerance: S) -> S
+    where S: Float,
+          F: FnMut(S) -> (S, S),
+{
+    let two: S = scalar(2.0);
+    let lo_is_negative = f_df(lo).0 < S::zero();
+    let mut x = lo + (hi - lo) / two;
+    for _ in 0..MAX_BISECTIONS {
+        let (f, df) = f_df(x);
+        if f.abs() <= tolerance {
+            return x;
+        }
+        if (f < S::zero()) == lo_is_negative {
+            lo = x;
+        } else {
+            hi = x;
+        }
+        let newton = x - f / df;
+        x = if df != S::zero() && newton > lo && newton < hi {
+            newton
+        } else {
+            let mid = lo + (hi - lo) / two;
+            if mid <= lo || mid >= hi {
+                return mid;
+            }
+            mid
+        };
+    }
+    x
+}
+
+
+/// The derivative of `f` at `x` by finite differences, where `f` is only defined between `lo`
+/// and `hi`.
+///
+/// Uses the central difference, or a second-order one-sided difference where `x` lies too close
+/// to either end of the range.
+pub fn derivative<S, F>(mut f: F, x: S, lo: S, hi: S) -> S
+    where S: Float,
+          F: FnMut(S) -> S,
+{
+    // The cube root of the epsilon balances truncation and rounding error.
+    let h = S::epsilon().cbrt() * (hi - lo).max(x.abs());
+    let (two, three, four): (S, S, S) = (scalar(2.0), scalar(3.0), scalar(4.0));
+    if hi - lo < two * h {
+        (f(hi) - f(lo)) / (hi - lo)
+    } else if x - h < lo {
+        (four * f(x + h) - three * f(x) - f(x + two * h)) / (two * h)
+    } else if x + h > hi {
+        (three * f(x) - four * f(x - h) + f(x - two * h)) / (two * h)
+    } else {
+        (f(x + h) - f(x - h)) / (two * h)
+    }
+}
+
+
+/// The minimum and maximum of `f` between `lo` and `hi` (inclusive), where `f` is only defined
+/// between `domain_lo` and `domain_hi`.
+///
+/// Along with the ends of the range, `f` is evaluated at every root of its derivative as found by
+/// `roots`. Roots are also found at any cusps at which the derivative changes sign.
+pub fn range<S, F>(f: F, lo: S, hi: S, domain_lo: S, domain_hi: S) -> (S, S)
+    where S: Float,
+          F: Fn(S) -> S,
+{
+    let (f_lo, f_hi) = (f(lo), f(hi));
+    let mut range = (f_lo.min(f_hi), f_lo.max(f_hi));
+    let mut extrema = vec![];
+    roots(|x| derivative(&f, x, domain_lo, domain_hi), lo, hi, &mut extrema);
+    for x in extrema {
+        let y = f(x);
+        range = (range.0.min(y), range.1.max(y));
+    }
+    range
+}
+
+
+/// Push every root of `f` between `lo` and `hi` (inclusive) onto `roots` in ascending order.
+///
+/// The range is divided into `SUBDIVISIONS` sub-intervals, each of which is bisected if `f`
+/// changes sign within it.
+pub fn roots<S, F>(mut f: F, lo: S, hi: S, roots: &mut Vec<S>)
+    where S: Float,
+          F: FnMut(S) -> S,
+{
+    let n: S = scalar(SUBDIVISIONS);
+    let mut a = lo;
+    let mut f_a = f(lo);
+    for i in 1..SUBDIVISIONS + 1 {
+        let b = if i == SUBDIVISIONS { hi } else { lo + (hi - lo) * scalar(i) / n };
+        let f_b = f(b);
+        if f_a == S::zero() {
+            roots.push(a);
+        } else if f_b != S::zero() && (f_a < S::zero()) != (f_b < S::zero()) {
+            roots.push(bisect(&mut f, a, b, f_a));
+        }
+        a = b;
+        f_a = f_b;
+    }
+    if f_a == S::zero() {
+        roots.push(a);
+    }
+}
+
+
+/// The integral of `f` from `lo` to `hi` using adaptive Simpson's rule.
+///
+/// The range is divided into sub-intervals, each of which is halved until its error estimate lies
+/// within its share of `tolerance`.
+pub fn integrate<S, F>(mut f: F, lo: S, hi: S, tolerance: S) -> S
+    where S: Float,
+          F: FnMut(S) -> S,
+{
+    let n: S = scalar(INTEGRATION_SUBDIVISIONS);
+    let tolerance = tolerance / n;
+    let mut a = (lo, f(lo));
+    let mut sum = S::zero();
+    for i in 1..INTEGRATION_SUBDIVISIONS + 1 {
+        let b_x = if i == INTEGRATION_SUBDIVISIONS { hi } else { lo + (hi - lo) * scalar(i) / n };
+        let b = (b_x, f(b_x));
+        let mid_x = a.0 + (b.0 - a.0) / scalar(2.0);
+        let mid = (mid_x, f(mid_x));
+        let whole = simpson(a, mid, b);
+        sum = sum + adaptive_simpson(&mut f, a, mid, b, whole, tolerance, MAX_INTEGRATION_DEPTH);
+        a = b;
+    }
+    sum
+}
+
+/// Simpson's rule over the interval from `lo` to `hi`, where each is an `(x, f(x))` pair.
+#[inline]
+fn simpson<S>(lo: (S, S), mid: (S, S), hi: (S, S)) -> S
+    where S: Float,
+{
+    (hi.0 - lo.0) / scalar(6.0) * (lo.1 + scalar::<S, _>(4.0) * mid.1 + hi.1)
+}
+
+/// Refine `whole`, the Simpson's rule estimate from `lo` to `hi`, by halving the interval until
+/// the estimates agree within `tolerance`.
+fn adaptive_simpson<S, F>(f: &mut F, lo: (S, S), mid: (S, S), hi: (S, S), whole: S, tolerance: S,
+                          depth: usize) -> S
+    where S: Float,
+          F: FnMut(S) -> S,
+{
+    let two: S = scalar(2.0);
+    let left_mid_x = lo.0 + (mid.0 - lo.0) / two;
+    let right_mid_x = mid.0 + (hi.0 - mid.0) / two;
+    let left_mid = (left_mid_x, f(left_mid_x));
+    let right_mid = (right_mid_x, f(right_mid_x));
+    let left = simpson(lo, left_mid, mid);
+    let right = simpson(mid, right_mid, hi);
+    let error = left + right - whole;
+    // Stop once the error can no longer be distinguished from rounding error.
+    let precision = S::epsilon() * (left.abs() + right.abs());
+    if depth == 0 || error.abs() <= scalar::<S, _>(15.0) * tolerance.max(precision) {
+        return left + right + error / scalar(15.0);
+    }
+    adaptive_simpson(f, lo, left_mid, mid, left, tolerance / two, depth - 1)
+        + adaptive_simpson(f, mid, right_mid, hi, right, tolerance / two, depth - 1)
+}
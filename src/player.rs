@@ -0,0 +1,173 @@
+use envelope::{Envelope, Steps};
+use num::NumCast;
+use numeric::scalar;
+use point::Breakpoint;
+use std::ops::{Add, Sub};
+
+
+/// Describes how a `Player` responds to being triggered while it is already playing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Retrigger {
+    /// Jump straight back to the start of the envelope.
+    ///
+    /// This is the simplest policy, but the jump from the current level to the start of the
+    /// envelope may be heard as a click.
+    Reset,
+    /// Ignore the trigger and carry on from the current position.
+    Legato,
+    /// Restart from the start of the envelope, but begin from the current level.
+    ///
+    /// The segment containing the player's `start` is remapped so that it runs from the current
+    /// level at `start` to the end of the segment while retaining its shape. All following
+    /// segments are unaffected.
+    FromCurrent,
+}
+
+/// Plays an `Envelope` one `step` at a time from some `start` each time it is triggered.
+///
+/// Triggering takes effect from the very next step, so a trigger issued between two samples is
+/// sample accurate. See `Retrigger` for how a trigger that arrives during playback is handled.
+#[derive(Clone)]
+pub struct Player<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    env: &'a E,
+    start: E::X,
+    step: E::X,
+    retrigger: Retrigger,
+    maybe_steps: Option<Steps<'a, E>>,
+    /// The level from which the segment containing `start` is remapped for
+    /// `Retrigger::FromCurrent`.
+    maybe_remap: Option<Remap<E::X>>,
+    /// The most recently yielded `y`.
+    maybe_level: Option<E::Y>,
+}
+
+/// Maps the rest of the segment from `start.0` so that it begins at `from` rather than `start.1`.
+#[derive(Copy, Clone, Debug)]
+struct Remap<X> {
+    from: f64,
+    start: (f64, f64),
+    end: (X, f64),
+}
+
+
+impl<'a, E> Player<'a, E>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X> + NumCast,
+          E::Y: NumCast,
+{
+    /// A stopped player that plays the given envelope from `start` by `step` when triggered.
+    #[inline]
+    pub fn new(env: &'a E, start: E::X, step: E::X, retrigger: Retrigger) -> Self {
+        Player {
+            env,
+            start,
+            step,
+            retrigger,
+            maybe_steps: None,
+            maybe_remap: None,
+            maybe_level: None,
+        }
+    }
+
+    /// The policy used when triggered during playback.
+    #[inline]
+    pub fn retrigger(&self) -> Retrigger {
+        self.retrigger
+    }
+
+    /// Change the policy used when triggered during playback.
+    #[inline]
+    pub fn set_retrigger(&mut self, retrigger: Retrigger) {
+        self.retrigger = retrigger;
+    }
+
+    /// Whether or not the player is part way through the envelope.
+    #[inline]
+    pub fn is_playing(&self) -> bool {
+        self.maybe_steps.is_some()
+    }
+
+    /// The most recently yielded `y`, if the player is playing.
+    #[inline]
+    pub fn level(&self) -> Option<E::Y>
+        where E::Y: Clone,
+    {
+        self.maybe_level.clone()
+    }
+
+    /// Start playing the envelope from the start, according to the `Retrigger` policy if the
+    /// player is already playing.
+    pub fn trigger(&mut self) {
+        let maybe_from = match (self.retrigger, self.is_playing()) {
+            (Retrigger::Legato, true) => return,
+            (Retrigger::FromCurrent, true) => self.maybe_level.take(),
+            _ => None,
+        };
+        self.maybe_steps = self.env.steps(self.start.clone(), self.step.clone());
+        self.maybe_level = None;
+
+        // Remap the rest of the segment containing `start` to begin from the current level.
+        self.maybe_remap = maybe_from.and_then(|from| {
+            let start = self.start.clone();
+            let end_idx = self.env.point_idx_on_or_before(start.clone()).map_or(0, |i| i + 1);
+            let end = self.env.points().nth(end_idx)?;
+            let start_y = self.env.y(start.clone())?;
+            Some(Remap {
+                from: scalar(from),
+                start: (scalar(start), scalar(start_y)),
+                end: (end.x(), scalar(end.y())),
+            })
+        });
+    }
+
+    /// Stop playing until the next trigger.
+    #[inline]
+    pub fn stop(&mut self) {
+        self.maybe_steps = None;
+        self.maybe_remap = None;
+        self.maybe_level = None;
+    }
+}
+
+impl<'a, E> Iterator for Player<'a, E>
+    where E: Envelope<'a>,
+          E::X: Add<Output=E::X> + Sub<Output=E::X> + NumCast,
+          E::Y: NumCast + Clone,
+{
+    type Item = E::Y;
+    /// Yields the next step of the envelope, or `None` if the player is stopped or has reached
+    /// the end of the envelope.
+    fn next(&mut self) -> Option<E::Y> {
+        let (x, mut y) = match self.maybe_steps.as_mut().and_then(|steps| steps.next_xy()) {
+            Some(xy) => xy,
+            None => {
+                self.stop();
+                return None;
+            },
+        };
+
+        if let Some(remap) = self.maybe_remap.clone() {
+            if x < remap.end.0 {
+                let (start_x, start_y) = remap.start;
+                let (end_x, end_y): (f64, f64) = (scalar(remap.end.0.clone()), remap.end.1);
+                let value: f64 = scalar(y);
+                let remapped = if end_y != start_y {
+                    // Scale the segment so that it spans from the level to its end.
+                    remap.from + (value - start_y) * (end_y - remap.from) / (end_y - start_y)
+                } else {
+                    // A flat segment has no range to scale, so fade out the offset instead.
+                    let progress = (scalar::<f64, _>(x) - start_x) / (end_x - start_x);
+                    value + (remap.from - start_y) * (1.0 - progress)
+                };
+                y = scalar(remapped);
+            } else {
+                self.maybe_remap = None;
+            }
+        }
+
+        self.maybe_level = Some(y.clone());
+        Some(y)
+    }
+}
@@ -1,7 +1,10 @@
 use interpolate::{self, Scalar};
 use interpolation::Spatial;
+use shape::SegmentShape;
 
 /// Implement this for types to be used as points on an Envelope.
+///
+/// Points whose `Y` can't be `Spatial` may implement `Breakpoint` instead.
 pub trait Point: Clone {
     type X: PartialEq + Clone;
     type Y: PartialEq + Spatial;
@@ -19,5 +22,98 @@ pub trait Point: Clone {
     {
         interpolate::linear(x, start, end)
     }
+    /// Interpolate between two points with access to the points on either side of the segment
+    /// and return y for the given x.
+    ///
+    /// `prev` is the point before `start` and `next` is the point after `end`, if there are any.
+    /// This is what `Envelope::y` and friends call. By default the neighbours are ignored and
+    /// `interpolate` is used. Override this for curves (such as splines) whose shape depends on
+    /// more than two points.
+    #[inline]
+    fn interpolate_with_neighbours(x: Self::X,
+                                   _prev: Option<&Self>,
+                                   start: &Self,
+                                   end: &Self,
+                                   _next: Option<&Self>) -> Self::Y
+        where <Self::Y as Spatial>::Scalar: Scalar,
+    {
+        Self::interpolate(x, start, end)
+    }
+    /// The shape of the segment between `start` and `end`, if it is one of the `SegmentShape`s.
+    ///
+    /// Describing the shape allows queries such as `Envelope::xs_at_y` to be solved analytically.
+    /// By default `None` is returned, in which case such queries are solved numerically via
+    /// `interpolate`.
+    #[inline]
+    fn segment_shape(_start: &Self, _end: &Self)
+        -> Option<SegmentShape<<Self::Y as Spatial>::Scalar>>
+    {
+        None
+    }
+}
+
+
+/// The interface shared by all points that may be owned by an `Envelope`.
+///
+/// Unlike `Point`, the `Y` of a `Breakpoint` need not be `Spatial`, so it may be a `bool`, an
+/// integer or an enum (see `StepPoint`). `Breakpoint` is implemented for every `Point`, so it only
+/// needs to be implemented directly for points whose values can't be interpolated spatially.
+pub trait Breakpoint: Clone {
+    type X: PartialEq + Clone;
+    type Y: PartialEq;
+
+    /// X (often associated with time).
+    fn x(&self) -> Self::X;
+    /// Y (often associated with some value).
+    fn y(&self) -> Self::Y;
+    /// Interpolate between `start` and `end` and return y for the given x.
+    ///
+    /// `prev` is the point before `start` and `next` is the point after `end`, if there are any.
+    /// For a `Point` this is `Point::interpolate_with_neighbours`.
+    fn interpolate_segment(x: Self::X,
+                           prev: Option<&Self>,
+                           start: &Self,
+                           end: &Self,
+                           next: Option<&Self>) -> Self::Y;
+    /// Extend the segment between `start` and `end` to an `x` that lies beyond `start`.
+    ///
+    /// This is used by `Extrapolation::Linear`. For a `Point` the straight line through both
+    /// points is extended.
+    fn extrapolate_segment(x: Self::X, start: &Self, end: &Self) -> Self::Y;
 }
 
+
+impl<P> Breakpoint for P
+    where P: Point,
+          <P::Y as Spatial>::Scalar: Scalar,
+{
+    type X = P::X;
+    type Y = P::Y;
+
+    #[inline(always)]
+    fn x(&self) -> P::X { Point::x(self) }
+    #[inline(always)]
+    fn y(&self) -> P::Y { Point::y(self) }
+    #[inline(always)]
+    fn interpolate_segment(x: P::X,
+                           prev: Option<&Self>,
+                           start: &Self,
+                           end: &Self,
+                           next: Option<&Self>) -> P::Y
+    {
+        P::interpolate_with_neighbours(x, prev, start, end, next)
+    }
+    #[inline(always)]
+    fn extrapolate_segment(x: P::X, start: &Self, end: &Self) -> P::Y {
+        interpolate::linear(x, start, end)
+    }
+}
+
+
+/// Implement this for `Breakpoint` types whose position may be edited in place.
+pub trait PointMut: Breakpoint {
+    /// Set the X (often associated with time).
+    fn set_x(&mut self, x: Self::X);
+    /// Set the Y (often associated with some value).
+    fn set_y(&mut self, y: Self::Y);
+}
@@ -0,0 +1,233 @@
+use envelope::Envelope;
+use interpolation::{Ease, Spatial};
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
+use shape::SegmentShape;
+
+
+/// The scalar type of the `y` values of the envelope `E`.
+type Scalar<'a, E> = <<E as Envelope<'a>>::Y as Spatial>::Scalar;
+
+/// The range of an envelope on the x axis and y axis, as returned by `Envelope::bounds`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bounds<X, Y> {
+    /// The `x` of the first and last points.
+    pub x: (X, X),
+    /// The minimum and maximum `y`.
+    pub y: (Y, Y),
+}
+
+/// A precomputed index of the range of every segment of the envelope `E`, allowing for fast
+/// `y_range` queries over large envelopes.
+///
+/// The index must be rebuilt if the envelope's points change.
+#[derive(Clone)]
+pub struct RangeIndex<'a, E>
+    where E: Envelope<'a> + 'a,
+          E::Y: Spatial,
+          <E::Y as Spatial>::Scalar: Float,
+{
+    points: Vec<&'a E::Point>,
+    xs: Vec<Scalar<'a, E>>,
+    /// A segment tree over the range of each segment, whose leaves begin at the number of
+    /// segments.
+    tree: Vec<(Scalar<'a, E>, Scalar<'a, E>)>,
+}
+
+
+/// The minimum and maximum `y` of the envelope between `x0` and `x1` (inclusive).
+pub fn y_range<'a, E>(env: &'a E, x0: E::X, x1: E::X) -> Option<(E::Y, E::Y)>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    let (lo, hi) = ordered(E::Point::x_to_scalar(x0), E::Point::x_to_scalar(x1));
+    let mut points = env.points();
+    let first = points.next()?;
+    let last = points.clone().next_back().unwrap_or(first);
+    if lo < x_of(first) || hi > x_of(last) {
+        return None;
+    }
+    if points.len() == 0 {
+        let y: <E::Y as Spatial>::Scalar = scalar(Breakpoint::y(first));
+        return Some((scalar(y), scalar(y)));
+    }
+
+    // With random access to the points, skip straight to the first segment ending on or after
+    // `lo`. `Skip` defers to `nth`, which is constant time for slice iterators.
+    let skip = env.points_slice()
+        .map_or(0, |points| points[1..].partition_point(|point| x_of(point) < lo));
+    let mut points = env.points().skip(skip.saturating_sub(1));
+    let mut maybe_prev = if skip > 0 { points.next() } else { None };
+    let mut left = points.next()?;
+
+    let mut maybe_range: Option<(_, _)> = None;
+    while let Some(right) = points.next() {
+        if x_of(left) > hi {
+            break;
+        }
+        if x_of(right) >= lo {
+            let maybe_next = points.clone().next();
+            let range = segment_range(maybe_prev, left, right, maybe_next, lo, hi);
+            maybe_range = Some(union(maybe_range, range));
+        }
+        maybe_prev = Some(left);
+        left = right;
+    }
+    maybe_range.map(|(min, max)| (scalar(min), scalar(max)))
+}
+
+
+impl<'a, E> RangeIndex<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    /// Index the range of every segment of the given envelope.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    pub fn new(env: &'a E) -> Self {
+        let points: Vec<&'a E::Point> = env.points().collect();
+        let xs: Vec<_> = points.iter().map(|&p| x_of(p)).collect();
+        let n = points.len().saturating_sub(1);
+        let mut tree = vec![(scalar(0.0), scalar(0.0)); 2 * n];
+        for i in 0..n {
+            let range = index_segment_range(&points, i, xs[i], xs[i + 1]);
+            tree[n + i] = range;
+        }
+        for i in (1..n).rev() {
+            tree[i] = union(Some(tree[2 * i]), tree[2 * i + 1]);
+        }
+        RangeIndex { points, xs, tree }
+    }
+
+    /// The minimum and maximum `y` of the envelope between `x0` and `x1` (inclusive).
+    ///
+    /// The result is the same as that of `Envelope::y_range`, but only the segments at either end
+    /// of the range are evaluated.
+    pub fn y_range(&self, x0: E::X, x1: E::X) -> Option<(E::Y, E::Y)> {
+        let (lo, hi) = ordered(E::Point::x_to_scalar(x0), E::Point::x_to_scalar(x1));
+        let xs = &self.xs;
+        match xs.len() {
+            0 => return None,
+            _ if lo < xs[0] || hi > xs[xs.len() - 1] => return None,
+            1 => {
+                let y = Breakpoint::y(self.points[0]);
+                let y: <E::Y as Spatial>::Scalar = scalar(y);
+                return Some((scalar(y), scalar(y)));
+            },
+            _ => (),
+        }
+
+        // The first segment ending on or after `lo` and the last starting on or before `hi`.
+        let n = xs.len() - 1;
+        let first = xs[1..].partition_point(|&x| x < lo).min(n - 1);
+        let last = xs.partition_point(|&x| x <= hi).saturating_sub(1).min(n - 1);
+        let mut range = index_segment_range(&self.points, first, lo.max(xs[first]),
+                                            hi.min(xs[first + 1]));
+        if last > first {
+            let end = index_segment_range(&self.points, last, lo.max(xs[last]), hi);
+            range = union(Some(range), end);
+            if let Some(inner) = self.query(first + 1, last) {
+                range = union(Some(range), inner);
+            }
+        }
+        Some((scalar(range.0), scalar(range.1)))
+    }
+
+    /// The range over the segments from `start` up to but not including `end`.
+    fn query(&self, start: usize, end: usize)
+        -> Option<(Scalar<'a, E>, Scalar<'a, E>)>
+    {
+        let n = self.tree.len() / 2;
+        let (mut lo, mut hi) = (start + n, end + n);
+        let mut maybe_range = None;
+        while lo < hi {
+            if lo % 2 == 1 {
+                maybe_range = Some(union(maybe_range, self.tree[lo]));
+                lo += 1;
+            }
+            if hi % 2 == 1 {
+                hi -= 1;
+                maybe_range = Some(union(maybe_range, self.tree[hi]));
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+        maybe_range
+    }
+}
+
+
+/// The range of the `i`th segment between `lo` and `hi`.
+fn index_segment_range<P>(points: &[&P], i: usize, lo: <P::Y as Spatial>::Scalar,
+                          hi: <P::Y as Spatial>::Scalar)
+    -> (<P::Y as Spatial>::Scalar, <P::Y as Spatial>::Scalar)
+    where P: Point,
+          P::X: NumCast,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float + Ease,
+{
+    let maybe_prev = i.checked_sub(1).map(|i| points[i]);
+    let maybe_next = points.get(i + 2).cloned();
+    segment_range(maybe_prev, points[i], points[i + 1], maybe_next, lo, hi)
+}
+
+/// The range of the segment from `left` to `right` between `lo` and `hi`, clamped to the segment.
+fn segment_range<P>(maybe_prev: Option<&P>, left: &P, right: &P, maybe_next: Option<&P>,
+                    lo: <P::Y as Spatial>::Scalar, hi: <P::Y as Spatial>::Scalar)
+    -> (<P::Y as Spatial>::Scalar, <P::Y as Spatial>::Scalar)
+    where P: Point,
+          P::X: NumCast,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float + Ease,
+{
+    let start = (x_of(left), scalar(Breakpoint::y(left)));
+    let end = (x_of(right), scalar(Breakpoint::y(right)));
+    let (lo, hi) = (lo.max(start.0), hi.min(end.0));
+    match Point::segment_shape(left, right) {
+        Some(shape) => shape.y_range(start, end, lo, hi),
+        // Without a known shape, even a segment between equal `y`s may bend between them, so only
+        // vertical segments (which have no interior) are solved directly.
+        None => if start.0 == end.0 {
+            SegmentShape::Linear.y_range(start, end, lo, hi)
+        } else {
+            let f = |x| -> <P::Y as Spatial>::Scalar {
+                scalar(Breakpoint::interpolate_segment(scalar(x), maybe_prev, left, right,
+                                                       maybe_next))
+            };
+            numeric::range(f, lo, hi, start.0, end.0)
+        },
+    }
+}
+
+/// The `x` of the given point as a scalar.
+#[inline]
+fn x_of<P>(point: &P) -> <P::Y as Spatial>::Scalar
+    where P: Point,
+          <P::Y as Spatial>::Scalar: Float,
+{
+    P::x_to_scalar(Breakpoint::x(point))
+}
+
+#[inline]
+fn ordered<S>(a: S, b: S) -> (S, S)
+    where S: PartialOrd,
+{
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+#[inline]
+fn union<S>(maybe_range: Option<(S, S)>, range: (S, S)) -> (S, S)
+    where S: Float,
+{
+    match maybe_range {
+        Some((min, max)) => (min.min(range.0), max.max(range.1)),
+        None => range,
+    }
+}
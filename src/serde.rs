@@ -207,3 +207,356 @@ mod bezier_point {
         assert_eq!(point, deserialized);
     }
 }
+
+mod jump {
+    use interpolate::Jump;
+    use super::serde;
+
+    impl serde::Serialize for Jump {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            match *self {
+                Jump::End => serializer.serialize_unit_variant("Jump", 0, "End"),
+                Jump::Start => serializer.serialize_unit_variant("Jump", 1, "Start"),
+                Jump::At(fraction) =>
+                    serializer.serialize_newtype_variant("Jump", 2, "At", fraction),
+            }
+        }
+    }
+
+    impl serde::Deserialize for Jump {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Jump, D::Error>
+            where D: serde::de::Deserializer,
+        {
+            enum Variant { End, Start, At }
+
+            impl serde::Deserialize for Variant {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    where D: serde::de::Deserializer,
+                {
+                    struct VariantVisitor;
+
+                    impl serde::de::Visitor for VariantVisitor {
+                        type Value = Variant;
+
+                        fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                "End" => Ok(Variant::End),
+                                "Start" => Ok(Variant::Start),
+                                "At" => Ok(Variant::At),
+                                _ => Err(serde::de::Error::custom("expected End, Start or At")),
+                            }
+                        }
+                    }
+
+                    deserializer.deserialize(VariantVisitor)
+                }
+            }
+
+            struct Visitor;
+
+            impl serde::de::EnumVisitor for Visitor {
+                type Value = Jump;
+
+                fn visit<V>(&mut self, mut visitor: V) -> Result<Jump, V::Error>
+                    where V: serde::de::VariantVisitor,
+                {
+                    match visitor.visit_variant()? {
+                        Variant::End => { visitor.visit_unit()?; Ok(Jump::End) },
+                        Variant::Start => { visitor.visit_unit()?; Ok(Jump::Start) },
+                        Variant::At => Ok(Jump::At(visitor.visit_newtype()?)),
+                    }
+                }
+            }
+
+            static VARIANTS: &[&str] = &["End", "Start", "At"];
+            deserializer.deserialize_enum("Jump", VARIANTS, Visitor)
+        }
+    }
+}
+
+mod segment_shape {
+    use interpolation::EaseFunction;
+    use shape::{SegmentShape, EASE_FUNCTIONS};
+    use std;
+    use super::serde;
+
+    impl<T> serde::Serialize for SegmentShape<T>
+        where T: serde::Serialize + Copy,
+    {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            const NAME: &str = "SegmentShape";
+            match *self {
+                SegmentShape::Linear => serializer.serialize_unit_variant(NAME, 0, "Linear"),
+                SegmentShape::Ease(ease_fn) => {
+                    let &(_, name) = EASE_FUNCTIONS.iter().find(|&&(f, _)| f == ease_fn).unwrap();
+                    serializer.serialize_newtype_variant(NAME, 1, "Ease", name)
+                },
+                SegmentShape::Bezier(curve) =>
+                    serializer.serialize_newtype_variant(NAME, 2, "Bezier", curve),
+                SegmentShape::Step(jump) =>
+                    serializer.serialize_newtype_variant(NAME, 3, "Step", jump),
+                SegmentShape::Exponential(k) =>
+                    serializer.serialize_newtype_variant(NAME, 4, "Exponential", k),
+                SegmentShape::Cosine => serializer.serialize_unit_variant(NAME, 5, "Cosine"),
+            }
+        }
+    }
+
+    impl<T> serde::Deserialize for SegmentShape<T>
+        where T: serde::Deserialize,
+    {
+        fn deserialize<D>(deserializer: &mut D) -> Result<SegmentShape<T>, D::Error>
+            where D: serde::de::Deserializer,
+        {
+            enum Variant { Linear, Ease, Bezier, Step, Exponential, Cosine }
+
+            impl serde::Deserialize for Variant {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    where D: serde::de::Deserializer,
+                {
+                    struct VariantVisitor;
+
+                    impl serde::de::Visitor for VariantVisitor {
+                        type Value = Variant;
+
+                        fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                "Linear" => Ok(Variant::Linear),
+                                "Ease" => Ok(Variant::Ease),
+                                "Bezier" => Ok(Variant::Bezier),
+                                "Step" => Ok(Variant::Step),
+                                "Exponential" => Ok(Variant::Exponential),
+                                "Cosine" => Ok(Variant::Cosine),
+                                _ => Err(serde::de::Error::custom("expected a SegmentShape")),
+                            }
+                        }
+                    }
+
+                    deserializer.deserialize(VariantVisitor)
+                }
+            }
+
+            struct Visitor<T> {
+                t: std::marker::PhantomData<T>,
+            }
+
+            impl<T> serde::de::EnumVisitor for Visitor<T>
+                where T: serde::Deserialize,
+            {
+                type Value = SegmentShape<T>;
+
+                fn visit<V>(&mut self, mut visitor: V) -> Result<SegmentShape<T>, V::Error>
+                    where V: serde::de::VariantVisitor,
+                {
+                    match visitor.visit_variant()? {
+                        Variant::Linear => { visitor.visit_unit()?; Ok(SegmentShape::Linear) },
+                        Variant::Ease => {
+                            let name: String = visitor.visit_newtype()?;
+                            match EASE_FUNCTIONS.iter().find(|&&(_, n)| n == name) {
+                                Some(&(ease_fn, _)) => Ok(SegmentShape::Ease(ease_fn)),
+                                None => Err(serde::de::Error::custom("unknown EaseFunction")),
+                            }
+                        },
+                        Variant::Bezier => Ok(SegmentShape::Bezier(visitor.visit_newtype()?)),
+                        Variant::Step => Ok(SegmentShape::Step(visitor.visit_newtype()?)),
+                        Variant::Exponential =>
+                            Ok(SegmentShape::Exponential(visitor.visit_newtype()?)),
+                        Variant::Cosine => { visitor.visit_unit()?; Ok(SegmentShape::Cosine) },
+                    }
+                }
+            }
+
+            static VARIANTS: &[&str] =
+                &["Linear", "Ease", "Bezier", "Step", "Exponential", "Cosine"];
+            let visitor = Visitor { t: std::marker::PhantomData };
+            deserializer.deserialize_enum("SegmentShape", VARIANTS, visitor)
+        }
+    }
+}
+
+mod any_point {
+    use any_point::AnyPoint;
+    use interpolation::{Ease, Spatial};
+    use num::{Float, NumCast};
+    use shape::SegmentShape;
+    use std;
+    use super::serde;
+
+    impl<X, Y> serde::Serialize for AnyPoint<X, Y>
+        where X: PartialOrd + NumCast + Clone + Copy + serde::Serialize,
+              Y: PartialEq + NumCast + Spatial + Clone + Copy + serde::Serialize,
+              <Y as Spatial>::Scalar: Float + Ease + serde::Serialize,
+    {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+
+            struct MapVisitor<'a, X, Y>
+                where X: PartialOrd + NumCast + Clone + Copy + 'a,
+                      Y: PartialEq + NumCast + Spatial + Clone + Copy + 'a,
+                      Y::Scalar: Float + Ease,
+            {
+                t: &'a AnyPoint<X, Y>,
+                field_idx: u8,
+            }
+
+            impl<'a, X, Y> serde::ser::MapVisitor for MapVisitor<'a, X, Y>
+                where X: PartialOrd + NumCast + Clone + Copy + serde::Serialize,
+                      Y: PartialEq + NumCast + Spatial + Clone + Copy + serde::Serialize,
+                      Y::Scalar: Float + Ease + serde::Serialize,
+            {
+                fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                    where S: serde::Serializer,
+                {
+                    match self.field_idx {
+                        0 => {
+                            self.field_idx += 1;
+                            Ok(Some(serializer.serialize_struct_elt("x", self.t.x)?))
+                        },
+                        1 => {
+                            self.field_idx += 1;
+                            Ok(Some(serializer.serialize_struct_elt("y", self.t.y)?))
+                        },
+                        2 => {
+                            self.field_idx += 1;
+                            Ok(Some(serializer.serialize_struct_elt("shape", self.t.shape)?))
+                        },
+                        _ => Ok(None),
+                    }
+                }
+            }
+
+            serializer.serialize_struct("AnyPoint", MapVisitor {
+                t: self,
+                field_idx: 0,
+            })
+        }
+    }
+
+    impl<X, Y> serde::Deserialize for AnyPoint<X, Y>
+        where X: PartialOrd + NumCast + Clone + Copy + serde::Deserialize,
+              Y: PartialEq + NumCast + Spatial + Clone + Copy + serde::Deserialize,
+              <Y as Spatial>::Scalar: Float + Ease + serde::Deserialize,
+    {
+        fn deserialize<D>(deserializer: &mut D) -> Result<AnyPoint<X, Y>, D::Error>
+            where D: serde::de::Deserializer
+        {
+
+            struct Visitor<X, Y> {
+                x: std::marker::PhantomData<X>,
+                y: std::marker::PhantomData<Y>,
+            }
+
+            impl<X, Y> serde::de::Visitor for Visitor<X, Y>
+                where X: PartialOrd + NumCast + Clone + Copy + serde::Deserialize,
+                      Y: PartialEq + NumCast + Spatial + Clone + Copy + serde::Deserialize,
+                      <Y as Spatial>::Scalar: Float + Ease + serde::Deserialize,
+            {
+                type Value = AnyPoint<X, Y>;
+
+                fn visit_map<V>(&mut self, mut visitor: V) -> Result<AnyPoint<X, Y>, V::Error>
+                    where V: serde::de::MapVisitor,
+                {
+
+                    enum Field { X, Y, Shape }
+
+                    impl serde::Deserialize for Field {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                            where D: serde::de::Deserializer,
+                        {
+                            struct FieldVisitor;
+
+                            impl serde::de::Visitor for FieldVisitor {
+                                type Value = Field;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "x" => Ok(Field::X),
+                                        "y" => Ok(Field::Y),
+                                        "shape" => Ok(Field::Shape),
+                                        _ => Err(serde::de::Error::custom("expected x, y or shape")),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(FieldVisitor)
+                        }
+                    }
+
+                    let mut x = None;
+                    let mut y = None;
+                    let mut shape: Option<SegmentShape<Y::Scalar>> = None;
+
+                    loop {
+                        match visitor.visit_key()? {
+                            Some(Field::X) => { x = Some(visitor.visit_value()?); },
+                            Some(Field::Y) => { y = Some(visitor.visit_value()?); },
+                            Some(Field::Shape) => { shape = Some(visitor.visit_value()?); },
+                            None => { break; }
+                        }
+                    }
+
+                    let x = match x {
+                        Some(x) => x,
+                        None => visitor.missing_field("x")?,
+                    };
+
+                    let y = match y {
+                        Some(y) => y,
+                        None => visitor.missing_field("y")?,
+                    };
+
+                    let shape = match shape {
+                        Some(shape) => shape,
+                        None => visitor.missing_field("shape")?,
+                    };
+
+                    visitor.end()?;
+
+                    Ok(AnyPoint { x, y, shape })
+                }
+            }
+
+            static FIELDS: &[&str] = &["x", "y", "shape"];
+            let visitor = Visitor {
+                x: std::marker::PhantomData,
+                y: std::marker::PhantomData,
+            };
+            deserializer.deserialize_struct("AnyPoint", FIELDS, visitor)
+        }
+    }
+
+
+    #[test]
+    fn test() {
+        extern crate serde_json;
+        use interpolate::Jump;
+        use interpolation::EaseFunction;
+
+        let shapes = [
+            SegmentShape::Linear,
+            SegmentShape::Ease(EaseFunction::BounceOut),
+            SegmentShape::Bezier(0.5),
+            SegmentShape::Step(Jump::End),
+            SegmentShape::Step(Jump::At(0.25)),
+            SegmentShape::Exponential(-2.0),
+            SegmentShape::Cosine,
+        ];
+        for &shape in &shapes {
+            let point = AnyPoint { x: 42, y: 5.0, shape };
+            let serialized = serde_json::to_string(&point).unwrap();
+            let deserialized: AnyPoint<i32, f64> = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(point, deserialized);
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use interpolate::Jump;
+use interpolation::{Ease, EaseFunction};
+use num::Float;
+use numeric::{self, scalar};
+
+
+/// Describes the curve of the segment between two points.
+///
+/// `Point` types that describe their segments via `Point::segment_shape` allow queries such as
+/// `Envelope::xs_at_y` to be solved analytically where possible.
+#[derive(Copy, Clone, PartialEq)]
+pub enum SegmentShape<S> {
+    /// A straight line, as produced by `interpolate::linear`.
+    Linear,
+    /// An eased curve, as produced by `interpolate::ease`.
+    Ease(EaseFunction),
+    /// A quadratic bezier curve with the given `curve` amount, as produced by
+    /// `interpolate::bezier`.
+    Bezier(S),
+    /// The start `y` is held until the `Jump`, as produced by `interpolate::step`.
+    Step(Jump),
+    /// An exponential curve with the given steepness `k`, as produced by
+    /// `interpolate::exponential`.
+    Exponential(S),
+    /// Half a period of a cosine wave, as produced by `interpolate::cosine`.
+    Cosine,
+}
+
+
+/// Each `EaseFunction` along with its name, as used by `Debug` and serialization.
+pub(crate) static EASE_FUNCTIONS: &[(EaseFunction, &str)] = &[
+    (EaseFunction::QuadraticIn, "QuadraticIn"),
+    (EaseFunction::QuadraticOut, "QuadraticOut"),
+    (EaseFunction::QuadraticInOut, "QuadraticInOut"),
+    (EaseFunction::CubicIn, "CubicIn"),
+    (EaseFunction::CubicOut, "CubicOut"),
+    (EaseFunction::CubicInOut, "CubicInOut"),
+    (EaseFunction::QuarticIn, "QuarticIn"),
+    (EaseFunction::QuarticOut, "QuarticOut"),
+    (EaseFunction::QuarticInOut, "QuarticInOut"),
+    (EaseFunction::QuinticIn, "QuinticIn"),
+    (EaseFunction::QuinticOut, "QuinticOut"),
+    (EaseFunction::QuinticInOut, "QuinticInOut"),
+    (EaseFunction::SineIn, "SineIn"),
+    (EaseFunction::SineOut, "SineOut"),
+    (EaseFunction::SineInOut, "SineInOut"),
+    (EaseFunction::CircularIn, "CircularIn"),
+    (EaseFunction::CircularOut, "CircularOut"),
+    (EaseFunction::CircularInOut, "CircularInOut"),
+    (EaseFunction::ExponentialIn, "ExponentialIn"),
+    (EaseFunction::ExponentialOut, "ExponentialOut"),
+    (EaseFunction::ExponentialInOut, "ExponentialInOut"),
+    (EaseFunction::ElasticIn, "ElasticIn"),
+    (EaseFunction::ElasticOut, "ElasticOut"),
+    (EaseFunction::ElasticInOut, "ElasticInOut"),
+    (EaseFunction::BackIn, "BackIn"),
+    (EaseFunction::BackOut, "BackOut"),
+    (EaseFunction::BackInOut, "BackInOut"),
+    (EaseFunction::BounceIn, "BounceIn"),
+    (EaseFunction::BounceOut, "BounceOut"),
+    (EaseFunction::BounceInOut, "BounceInOut"),
+];
+
+impl<S> SegmentShape<S>
+    where S: Float + Ease,
+{
+    /// The `y` of the segment from `start` to `end` at the given `x`, where each point is given
+    /// as an `(x, y)` pair of scalars.
+    pub fn y(&self, start: (S, S), end: (S, S), x: S) -> S {
+        let (x0, y0) = start;
+        let (x1, y1) = end;
+        if y0 == y1 || x == x0 {
+            return y0;
+        } else if x == x1 {
+            return y1;
+        }
+        let t = (x - x0) / (x1 - x0);
+        let diff = y1 - y0;
+        match *self {
+            SegmentShape::Step(Jump::End) => y0,
+            SegmentShape::Step(jump) => if t < scalar(jump.fraction()) { y0 } else { y1 },
+            SegmentShape::Linear => y0 + diff * t,
+            SegmentShape::Ease(ease_fn) => y0 + diff * Ease::calc(t, ease_fn),
+            SegmentShape::Bezier(curve) => {
+                let (a, b) = bezier_coefficients(diff, curve);
+                y0 + (a * t + b) * t
+            },
+            SegmentShape::Exponential(k) => if k.abs() <= S::epsilon() {
+                y0 + diff * t
+            } else {
+                y0 + diff * (k * t).exp_m1() / k.exp_m1()
+            },
+            SegmentShape::Cosine => y0 + diff * (S::one() - (t * pi()).cos()) / scalar(2.0),
+        }
+    }
+
+    /// The area beneath the segment from `start` to `end` between its start and the given `x`,
+    /// where each point is given as an `(x, y)` pair of scalars.
+    ///
+    /// Eased segments are integrated numerically while all other shapes are integrated
+    /// analytically. Returns zero for any `x` before the start, and the area of the whole segment
+    /// for any `x` beyond the end.
+    pub fn integral(&self, start: (S, S), end: (S, S), x: S) -> S {
+        let (x0, y0) = start;
+        let (x1, y1) = end;
+        let width = x1 - x0;
+        if width <= S::zero() || x <= x0 {
+            return S::zero();
+        }
+        let t = ((x - x0) / width).min(S::one());
+        if y0 == y1 {
+            return y0 * t * width;
+        }
+        let diff = y1 - y0;
+        let two: S = scalar(2.0);
+        let area = match *self {
+            SegmentShape::Step(jump) => {
+                let fraction = match jump {
+                    Jump::End => S::one(),
+                    jump => scalar(jump.fraction()),
+                };
+                if t < fraction { y0 * t } else { y0 * fraction + y1 * (t - fraction) }
+            },
+            SegmentShape::Linear => y0 * t + diff * t * t / two,
+            SegmentShape::Bezier(curve) => {
+                let (a, b) = bezier_coefficients(diff, curve);
+                y0 * t + b * t * t / two + a * t * t * t / scalar(3.0)
+            },
+            SegmentShape::Exponential(k) => if k.abs() <= S::epsilon() {
+                y0 * t + diff * t * t / two
+            } else {
+                y0 * t + diff * ((k * t).exp_m1() / k - t) / k.exp_m1()
+            },
+            SegmentShape::Cosine => y0 * t + diff * (t - (t * pi()).sin() / pi()) / two,
+            SegmentShape::Ease(_) => {
+                let tolerance = S::epsilon() * (y0.abs() + y1.abs()) * t;
+                let unit = |u| self.y((S::zero(), y0), (S::one(), y1), u);
+                numeric::integrate(unit, S::zero(), t, tolerance)
+            },
+        };
+        area * width
+    }
+
+    /// The slope of the segment from `start` to `end` at the given `x`, where each point is given
+    /// as an `(x, y)` pair of scalars.
+    ///
+    /// Eased segments are differentiated numerically while all other shapes are differentiated
+    /// analytically. The slope of a step is zero everywhere other than at its jump, where it is
+    /// undefined and given as zero. Vertical segments have an infinite slope.
+    pub fn dy_dx(&self, start: (S, S), end: (S, S), x: S) -> S {
+        let (x0, y0) = start;
+        let (x1, y1) = end;
+        let width = x1 - x0;
+        if y0 == y1 {
+            return S::zero();
+        } else if width == S::zero() {
+            return if y1 > y0 { S::infinity() } else { S::neg_infinity() };
+        }
+        let t = ((x - x0) / width).max(S::zero()).min(S::one());
+        let diff = y1 - y0;
+        let two: S = scalar(2.0);
+        let dy_dt = match *self {
+            SegmentShape::Step(_) => S::zero(),
+            SegmentShape::Linear => diff,
+            SegmentShape::Bezier(curve) => {
+                let (a, b) = bezier_coefficients(diff, curve);
+                two * a * t + b
+            },
+            SegmentShape::Exponential(k) => if k.abs() <= S::epsilon() {
+                diff
+            } else {
+                diff * k * (k * t).exp() / k.exp_m1()
+            },
+            SegmentShape::Cosine => diff * pi::<S>() * (t * pi()).sin() / two,
+            SegmentShape::Ease(_) => {
+                let unit = |u| self.y((S::zero(), y0), (S::one(), y1), u);
+                numeric::derivative(unit, t, S::zero(), S::one())
+            },
+        };
+        dy_dt / width
+    }
+
+    /// The minimum and maximum `y` of the segment from `start` to `end` between `lo` and `hi`
+    /// (inclusive), where each point is given as an `(x, y)` pair of scalars.
+    ///
+    /// The extremum within a bezier segment is found analytically, while those of eased segments
+    /// are found numerically. All other shapes are monotonic. Vertical segments span both `y`s.
+    pub fn y_range(&self, start: (S, S), end: (S, S), lo: S, hi: S) -> (S, S) {
+        let (x0, y0) = start;
+        let (x1, y1) = end;
+        if x0 == x1 {
+            return (y0.min(y1), y0.max(y1));
+        }
+        let (y_lo, y_hi) = (self.y(start, end, lo), self.y(start, end, hi));
+        let range = (y_lo.min(y_hi), y_lo.max(y_hi));
+        match *self {
+            SegmentShape::Bezier(curve) => {
+                // The quadratic `a*t^2 + b*t` has its extremum at `t = -b / 2a`.
+                let (a, b) = bezier_coefficients(y1 - y0, curve);
+                if a == S::zero() {
+                    return range;
+                }
+                let x = x0 + (x1 - x0) * -b / (a + a);
+                if x > lo && x < hi {
+                    let y = self.y(start, end, x);
+                    return (range.0.min(y), range.1.max(y));
+                }
+                range
+            },
+            SegmentShape::Ease(_) => {
+                numeric::range(|x| self.y(start, end, x), lo, hi, x0, x1)
+            },
+            _ => range,
+        }
+    }
+
+    /// Push every `x` at which the segment from `start` to `end` is equal to `y` onto `xs` in
+    /// ascending order, where each point is given as an `(x, y)` pair of scalars.
+    ///
+    /// Eased segments are solved numerically while all other shapes are solved analytically. Flat
+    /// segments lying on `y` produce only their start, and a step that jumps past `y` produces the
+    /// `x` of its jump.
+    pub fn xs_at_y(&self, start: (S, S), end: (S, S), y: S, xs: &mut Vec<S>) {
+        let (x0, y0) = start;
+        let (x1, y1) = end;
+
+        // Flat and vertical segments.
+        if y0 == y1 {
+            if y0 == y {
+                xs.push(x0);
+            }
+            return;
+        }
+        if x0 == x1 {
+            if (y0 - y) * (y1 - y) <= S::zero() {
+                xs.push(x0);
+            }
+            return;
+        }
+
+        let diff = y1 - y0;
+        let c = y - y0;
+        let x_at_t = |t: S| x0 + (x1 - x0) * t;
+        match *self {
+            SegmentShape::Step(jump) => {
+                if y0 == y {
+                    xs.push(x0);
+                } else if (y0 - y) * (y1 - y) <= S::zero() {
+                    let jump_x = match jump {
+                        Jump::End => x1,
+                        jump => x_at_t(scalar(jump.fraction())),
+                    };
+                    xs.push(jump_x);
+                }
+            },
+            SegmentShape::Linear => {
+                let t = c / diff;
+                if t >= S::zero() && t <= S::one() {
+                    xs.push(x_at_t(t));
+                }
+            },
+            SegmentShape::Bezier(curve) => {
+                // Solve `a*t^2 + b*t - c = 0` for `t` within the segment.
+                let (a, b) = bezier_coefficients(diff, curve);
+                let mut push_t = |t: S| if t >= S::zero() && t <= S::one() { xs.push(x_at_t(t)) };
+                if a.abs() <= S::epsilon() * b.abs() {
+                    push_t(c / b);
+                } else {
+                    let discriminant = b * b + scalar::<S, _>(4.0) * a * c;
+                    if discriminant >= S::zero() {
+                        let sqrt = discriminant.sqrt();
+                        let two_a = a + a;
+                        let (t1, t2) = ((-b - sqrt) / two_a, (-b + sqrt) / two_a);
+                        let (t1, t2) = if t1 <= t2 { (t1, t2) } else { (t2, t1) };
+                        push_t(t1);
+                        if t2 != t1 {
+                            push_t(t2);
+                        }
+                    }
+                }
+            },
+            SegmentShape::Exponential(k) => {
+                let u = c / diff;
+                let t = if k.abs() <= S::epsilon() { u } else { (u * k.exp_m1()).ln_1p() / k };
+                if t >= S::zero() && t <= S::one() {
+                    xs.push(x_at_t(t));
+                }
+            },
+            SegmentShape::Cosine => {
+                let u = c / diff;
+                if u >= S::zero() && u <= S::one() {
+                    let t = (S::one() - u - u).acos() / pi();
+                    xs.push(x_at_t(t));
+                }
+            },
+            SegmentShape::Ease(_) => {
+                numeric::roots(|x| self.y(start, end, x) - y, x0, x1, xs);
+            },
+        }
+    }
+}
+
+
+impl<S> ::std::fmt::Debug for SegmentShape<S>
+    where S: ::std::fmt::Debug,
+{
+    /// `EaseFunction` does not implement `Debug`, so eased segments are written with its name.
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            SegmentShape::Linear => f.write_str("Linear"),
+            SegmentShape::Ease(ease_fn) => {
+                let &(_, name) = EASE_FUNCTIONS.iter().find(|&&(e, _)| e == ease_fn).unwrap();
+                f.debug_tuple("Ease").field(&format_args!("{}", name)).finish()
+            },
+            SegmentShape::Bezier(ref curve) => f.debug_tuple("Bezier").field(curve).finish(),
+            SegmentShape::Step(ref jump) => f.debug_tuple("Step").field(jump).finish(),
+            SegmentShape::Exponential(ref k) => f.debug_tuple("Exponential").field(k).finish(),
+            SegmentShape::Cosine => f.write_str("Cosine"),
+        }
+    }
+}
+
+
+/// The coefficients `(a, b)` of the quadratic `a*t^2 + b*t` that describes the offset from the
+/// start `y` of a segment produced by `interpolate::bezier`.
+#[inline]
+pub fn bezier_coefficients<S>(diff_y: S, curve: S) -> (S, S)
+    where S: Float,
+{
+    // The control point lies at `y2 = diff_y / 2 * (1 + curve)` relative to the start, so the
+    // curve is `2*y2*t + (diff_y - 2*y2)*t^2`.
+    let b = diff_y * (S::one() + curve);
+    let a = diff_y - b;
+    (a, b)
+}
+
+
+#[inline]
+fn pi<S>() -> S
+    where S: Float,
+{
+    scalar(::std::f64::consts::PI)
+}
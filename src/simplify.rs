@@ -0,0 +1,122 @@
+use envelope::Envelope;
+use interpolation::{Ease, Spatial};
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
+use shape::SegmentShape;
+use std::collections::BTreeSet;
+
+
+/// The points of the envelope with as many as possible removed while keeping its `y` within
+/// `tolerance` of the original at every `x`, using the Ramer–Douglas–Peucker algorithm.
+///
+/// Each span of the envelope is replaced by a single segment between its first and last points
+/// unless the segment deviates from the original by more than `tolerance`, in which case the span
+/// is split and each half is simplified in turn. The deviation is measured
+/// in `y` after interpolation, including any extrema within curved segments. As removing a point
+/// may change the interpolation of segments that depend on their neighbours (e.g. splines), the
+/// neighbouring spans are re-checked after every split.
+pub fn simplify<'a, E>(env: &'a E, tolerance: <E::Y as Spatial>::Scalar) -> Vec<E::Point>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y> + Clone,
+{
+    let points: Vec<&'a E::Point> = env.points().collect();
+    if points.len() <= 2 {
+        return points.into_iter().cloned().collect();
+    }
+
+    let last = points.len() - 1;
+    let mut kept: BTreeSet<usize> = vec![0, last].into_iter().collect();
+    let mut spans = vec![(0, last)];
+    while let Some((start, end)) = spans.pop() {
+        // Skip spans that have since been split.
+        if kept.range(start + 1..end).next().is_some() {
+            continue;
+        }
+        let maybe_prev = kept.range(..start).next_back().cloned();
+        let maybe_next = kept.range(end + 1..).next().cloned();
+        let split = match split_point(&points, maybe_prev, start, end, maybe_next, tolerance) {
+            Some(split) => split,
+            None => continue,
+        };
+        kept.insert(split);
+        spans.push((start, split));
+        spans.push((split, end));
+        // The neighbours of the adjacent spans have changed.
+        if let Some(prev) = maybe_prev {
+            spans.push((prev, start));
+        }
+        if let Some(next) = maybe_next {
+            spans.push((end, next));
+        }
+    }
+
+    kept.into_iter().map(|i| points[i].clone()).collect()
+}
+
+
+/// The index of the point at which to split the span from `start` to `end`, if the single
+/// segment between them deviates from the original by more than `tolerance`.
+///
+/// The span is split at the point that deviates the most, or at the end of the worst segment if
+/// the deviation lies within a curved segment.
+fn split_point<P>(points: &[&P], maybe_prev: Option<usize>, start: usize, end: usize,
+                  maybe_next: Option<usize>, tolerance: <P::Y as Spatial>::Scalar)
+    -> Option<usize>
+    where P: Point,
+          P::X: NumCast,
+          P::Y: NumCast,
+          <P::Y as Spatial>::Scalar: Float + Ease,
+{
+    if end == start + 1 {
+        return None;
+    }
+    let x_of = |i: usize| P::x_to_scalar(Breakpoint::x(points[i]));
+    let y_of = |i: usize| -> <P::Y as Spatial>::Scalar { scalar(Breakpoint::y(points[i])) };
+
+    // A vertical span cannot represent the points between its ends.
+    if x_of(start) == x_of(end) {
+        return Some(start + 1);
+    }
+
+    let (left, right) = (points[start], points[end]);
+    let (span_prev, span_next) = (maybe_prev.map(|i| points[i]), maybe_next.map(|i| points[i]));
+    let span_y = |x: <P::Y as Spatial>::Scalar| -> <P::Y as Spatial>::Scalar {
+        scalar(Breakpoint::interpolate_segment(scalar(x), span_prev, left, right, span_next))
+    };
+    let span_is_linear = Point::segment_shape(left, right) == Some(SegmentShape::Linear);
+
+    // The `y` of each point is compared rather than that of the envelope, so that jumps are
+    // caught.
+    let point_deviation = |i: usize| (y_of(i) - span_y(x_of(i))).abs();
+    let (worst_point, worst_point_deviation) = (start + 1..end)
+        .map(|i| (i, point_deviation(i)))
+        .fold((start + 1, -scalar::<<P::Y as Spatial>::Scalar, _>(1.0)),
+              |worst, (i, d)| if d > worst.1 { (i, d) } else { worst });
+    if worst_point_deviation > tolerance {
+        return Some(worst_point);
+    }
+
+    // Check for any deviation within the segments.
+    for i in start..end {
+        let (x0, x1) = (x_of(i), x_of(i + 1));
+        let is_linear = Point::segment_shape(points[i], points[i + 1]) == Some(SegmentShape::Linear);
+        if x0 == x1 || (span_is_linear && is_linear) {
+            continue;
+        }
+        let (prev, next) = (i.checked_sub(1).map(|i| points[i]), points.get(i + 2).cloned());
+        let difference = |x| {
+            let y: <P::Y as Spatial>::Scalar = scalar(
+                Breakpoint::interpolate_segment(scalar(x), prev, points[i], points[i + 1], next));
+            y - span_y(x)
+        };
+        let (min, max) = numeric::range(difference, x0, x1, x0, x1);
+        if min.abs().max(max.abs()) > tolerance {
+            return Some(if i > start { i } else { i + 1 });
+        }
+    }
+    None
+}
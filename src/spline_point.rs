@@ -0,0 +1,104 @@
+use interpolate::{self, CatmullRom};
+use interpolation::Spatial;
+use num::{Float, NumCast};
+use point::{Point, PointMut};
+use shape::SegmentShape;
+
+
+/// The spline along which a `SplinePoint` is interpolated towards the following point.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Spline {
+    /// A Catmull-Rom spline passing through every point, with tangents determined by the
+    /// surrounding points.
+    CatmullRom(CatmullRom),
+    /// A monotone cubic curve passing through every point that never overshoots `y` of the
+    /// surrounding points.
+    Monotone,
+}
+
+
+/// A type whose interpolation may follow a spline through the surrounding points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplinePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    pub x: X,
+    pub y: Y,
+    pub maybe_spline: Option<Spline>,
+}
+
+
+impl<X, Y> SplinePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    /// Constructor for a SplinePoint.
+    #[inline]
+    pub fn new(x: X, y: Y, maybe_spline: Option<Spline>) -> SplinePoint<X, Y> {
+        SplinePoint {
+            x,
+            y,
+            maybe_spline,
+        }
+    }
+}
+
+
+impl<X, Y> Point for SplinePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    type X = X;
+    type Y = Y;
+
+    #[inline(always)]
+    fn x_to_scalar(x: X) -> Y::Scalar {
+        NumCast::from(x).unwrap()
+    }
+    #[inline(always)]
+    fn x(&self) -> X { self.x }
+    #[inline(always)]
+    fn y(&self) -> Y { self.y }
+    /// Interpolate as though `start` and `end` were the only points.
+    #[inline(always)]
+    fn interpolate(x: X, start: &Self, end: &Self) -> Y {
+        Self::interpolate_with_neighbours(x, None, start, end, None)
+    }
+    #[inline]
+    fn interpolate_with_neighbours(x: X,
+                                   prev: Option<&Self>,
+                                   start: &Self,
+                                   end: &Self,
+                                   next: Option<&Self>) -> Y
+    {
+        match start.maybe_spline {
+            Some(Spline::CatmullRom(catmull_rom)) =>
+                interpolate::catmull_rom(x, prev, start, end, next, catmull_rom),
+            Some(Spline::Monotone) => interpolate::monotone_cubic(x, prev, start, end, next),
+            None => interpolate::linear(x, start, end),
+        }
+    }
+    #[inline(always)]
+    fn segment_shape(start: &Self, _end: &Self) -> Option<SegmentShape<Y::Scalar>> {
+        match start.maybe_spline {
+            Some(_) => None,
+            None => Some(SegmentShape::Linear),
+        }
+    }
+}
+
+
+impl<X, Y> PointMut for SplinePoint<X, Y>
+    where X: PartialEq + NumCast + Clone + Copy,
+          Y: PartialEq + NumCast + Spatial + Clone + Copy,
+          Y::Scalar: Float,
+{
+    #[inline(always)]
+    fn set_x(&mut self, x: X) { self.x = x; }
+    #[inline(always)]
+    fn set_y(&mut self, y: Y) { self.y = y; }
+}
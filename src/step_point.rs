@@ -0,0 +1,74 @@
+use interpolate::{self, Jump};
+use num::NumCast;
+use point::{Breakpoint, PointMut};
+
+
+/// A type whose `y` is held for the whole segment until the following point.
+///
+/// As a `StepPoint` is never interpolated spatially, `Y` may be any type, such as a `bool` for a
+/// gate, an integer for a program change or an enum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + Clone,
+{
+    pub x: X,
+    pub y: Y,
+    /// The position within the segment at which `y` jumps to the next point's value.
+    pub jump: Jump,
+}
+
+
+impl<X, Y> StepPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + Clone,
+{
+    /// Constructor for a StepPoint.
+    #[inline]
+    pub fn new(x: X, y: Y, jump: Jump) -> StepPoint<X, Y> {
+        StepPoint {
+            x,
+            y,
+            jump,
+        }
+    }
+}
+
+
+impl<X, Y> Breakpoint for StepPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + Clone,
+{
+    type X = X;
+    type Y = Y;
+
+    #[inline(always)]
+    fn x(&self) -> X { self.x }
+    #[inline(always)]
+    fn y(&self) -> Y { self.y.clone() }
+    #[inline(always)]
+    fn interpolate_segment(x: X,
+                           _prev: Option<&Self>,
+                           start: &Self,
+                           end: &Self,
+                           _next: Option<&Self>) -> Y
+    {
+        interpolate::step(x, start, end, start.jump)
+    }
+    /// Steps are extended by holding the `y` of `start`.
+    #[inline(always)]
+    fn extrapolate_segment(_x: X, start: &Self, _end: &Self) -> Y {
+        start.y.clone()
+    }
+}
+
+
+impl<X, Y> PointMut for StepPoint<X, Y>
+    where X: PartialOrd + NumCast + Clone + Copy,
+          Y: PartialEq + Clone,
+{
+    #[inline(always)]
+    fn set_x(&mut self, x: X) { self.x = x; }
+    #[inline(always)]
+    fn set_y(&mut self, y: Y) { self.y = y; }
+}
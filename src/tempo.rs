@@ -0,0 +1,514 @@
+use envelope::{Cursor, Envelope};
+use interpolation::{Ease, EaseFunction, Spatial};
+use num::{Float, NumCast};
+use numeric::{self, scalar};
+use point::{Breakpoint, Point};
+use shape::{self, SegmentShape};
+use std::f64::consts::{LN_2, PI};
+
+
+/// Converts between positions in beats and in seconds, for some envelope of tempo in beats per
+/// minute over beats.
+///
+/// The number of seconds elapsed over each segment of the tempo envelope is given by the integral
+/// of `60 / bpm` over the segment. This integral and its inverse are solved exactly for `Linear`,
+/// `Step`, `Exponential`, `Cosine` and `Bezier` segments, along with the quadratic, exponential
+/// and `SineInOut` eases. Other eased segments and those with an unknown shape are integrated
+/// numerically to within the precision of an `f64`, and inverted via Newton's method.
+///
+/// The tempo is held at that of the first and last points beyond the range of the envelope, and
+/// must be greater than zero at all beats.
+#[derive(Clone)]
+pub struct TempoMap<'a, E>
+    where E: Envelope<'a> + 'a,
+{
+    points: Vec<&'a E::Point>,
+    /// The tempo at each point, along with the seconds elapsed since the first point.
+    marks: Vec<Mark>,
+    /// The shape of each segment, if it is known.
+    shapes: Vec<Option<SegmentShape<f64>>>,
+    /// The seconds elapsed from the first point to beat zero.
+    origin: f64,
+}
+
+/// The tempo at some beat, along with the seconds elapsed since the first point.
+#[derive(Copy, Clone, Debug)]
+struct Mark {
+    beat: f64,
+    bpm: f64,
+    secs: f64,
+}
+
+/// The beat reached some number of seconds after the first point, along with the index of the
+/// segment in which it lies.
+#[derive(Copy, Clone, Debug)]
+struct Position {
+    segment: usize,
+    secs: f64,
+    beat: f64,
+}
+
+/// A tempo curve over some fraction `u` of a segment whose reciprocal has a closed-form integral.
+#[derive(Copy, Clone, Debug)]
+enum Tempo {
+    Constant(f64),
+    /// `a * u^2 + b * u + c`.
+    Quadratic(f64, f64, f64),
+    /// `a + b * e^(k * u)`.
+    Exponential(f64, f64, f64),
+    /// Half a period of a cosine wave from the first tempo to the second over the whole segment.
+    Cosine(f64, f64),
+}
+
+/// The tempo over a whole segment, as one `Tempo` followed by an optional second from the given
+/// fraction of the segment onwards.
+#[derive(Copy, Clone, Debug)]
+struct Pieces {
+    first: Tempo,
+    maybe_second: Option<(f64, Tempo)>,
+}
+
+/// An iterator that samples the envelope `E` (whose `X` is measured in beats) at every `step` in
+/// seconds, using the tempo given by some `TempoMap`.
+///
+/// Returns `None` the first time a step falls out of range of all points in `env`, unless `env`
+/// provides some extrapolation via `Envelope::y`.
+#[derive(Clone)]
+pub struct BeatSteps<'a, 'b, E, T>
+    where 'a: 'b,
+          E: Envelope<'b> + 'b,
+          T: Envelope<'a> + 'a,
+{
+    tempo: &'b TempoMap<'a, T>,
+    cursor: Cursor<'b, E>,
+    start: f64,
+    step: f64,
+    n: u64,
+    /// The position of the last step, from which the next is solved.
+    maybe_last: Option<Position>,
+}
+
+
+impl<'a, E> TempoMap<'a, E>
+    where E: Envelope<'a>,
+          E::X: NumCast,
+          E::Y: NumCast + Spatial,
+          <E::Y as Spatial>::Scalar: Float + Ease,
+          E::Point: Point<X=E::X, Y=E::Y>,
+{
+    /// Construct a `TempoMap` from the given envelope of beats per minute over beats.
+    ///
+    /// Returns `None` if the envelope has no points, or if the tempo of any point is not a
+    /// positive, finite number of beats per minute.
+    ///
+    /// Note: This method assumes that the envelope points are ordered.
+    pub fn new(env: &'a E) -> Option<Self> {
+        let points: Vec<&'a E::Point> = env.points().collect();
+        let mut marks = Vec::with_capacity(points.len());
+        let mut shapes = Vec::with_capacity(points.len().saturating_sub(1));
+        for (i, &point) in points.iter().enumerate() {
+            let beat = E::Point::x_to_scalar(Breakpoint::x(point));
+            let bpm = scalar(Breakpoint::y(point));
+            marks.push(Mark { beat: scalar(beat), bpm, secs: 0.0 });
+            if let Some(&end) = points.get(i + 1) {
+                shapes.push(Point::segment_shape(point, end).map(to_f64));
+            }
+        }
+        if marks.is_empty() || marks.iter().any(|mark| !(mark.bpm > 0.0 && mark.bpm.is_finite())) {
+            return None;
+        }
+
+        let mut tempo = TempoMap { points, marks, shapes, origin: 0.0 };
+        for i in 1..tempo.marks.len() {
+            let end_beat = tempo.marks[i].beat;
+            tempo.marks[i].secs = tempo.marks[i - 1].secs + tempo.segment_secs(i - 1, end_beat);
+        }
+        tempo.origin = tempo.secs_since_first(0.0);
+        Some(tempo)
+    }
+
+    /// The tempo in beats per minute at the given beat.
+    pub fn bpm(&self, beat: f64) -> f64 {
+        let first = self.marks[0];
+        let last = self.marks[self.marks.len() - 1];
+        if beat <= first.beat {
+            first.bpm
+        } else if beat >= last.beat {
+            last.bpm
+        } else {
+            let i = self.marks.partition_point(|mark| mark.beat <= beat) - 1;
+            self.segment_bpm(i, beat)
+        }
+    }
+
+    /// The seconds elapsed from beat zero to the given beat.
+    #[inline]
+    pub fn seconds_at(&self, beat: f64) -> f64 {
+        self.secs_since_first(beat) - self.origin
+    }
+
+    /// The beat reached after the given number of seconds have elapsed since beat zero.
+    #[inline]
+    pub fn beat_at(&self, secs: f64) -> f64 {
+        self.beat_from(&mut None, secs)
+    }
+
+    /// The fractional sample index at which the given beat lies, counting from beat zero.
+    #[inline]
+    pub fn sample_at(&self, beat: f64, sample_rate: f64) -> f64 {
+        self.seconds_at(beat) * sample_rate
+    }
+
+    /// The beat at which the sample with the given index lies, counting from beat zero.
+    #[inline]
+    pub fn beat_at_sample(&self, sample: u64, sample_rate: f64) -> f64 {
+        self.beat_at(sample as f64 / sample_rate)
+    }
+
+    /// Sample the given envelope, whose `X` is measured in beats, at every `step` seconds starting
+    /// from `start` seconds after beat zero.
+    ///
+    /// The position of each step is calculated from its index, so no error accumulates.
+    #[inline]
+    pub fn steps<'b, B>(&'b self, env: &'b B, start: f64, step: f64) -> BeatSteps<'a, 'b, B, E>
+        where B: Envelope<'b>,
+              B::X: NumCast,
+    {
+        BeatSteps {
+            tempo: self,
+            cursor: env.cursor(),
+            start,
+            step,
+            n: 0,
+            maybe_last: None,
+        }
+    }
+
+    /// The beat reached after the given number of seconds have elapsed since beat zero.
+    ///
+    /// Where `maybe_last` is some earlier position, the segment is found by moving forwards from
+    /// it and any numeric solution only integrates the tempo since it. `maybe_last` is then set to
+    /// the position reached, so that consecutive calls are amortized O(1).
+    fn beat_from(&self, maybe_last: &mut Option<Position>, secs: f64) -> f64 {
+        let secs = secs + self.origin;
+        let first = self.marks[0];
+        let last = self.marks[self.marks.len() - 1];
+        if secs <= first.secs {
+            *maybe_last = None;
+            return first.beat + (secs - first.secs) * first.bpm / 60.0;
+        } else if secs >= last.secs {
+            *maybe_last = None;
+            return last.beat + (secs - last.secs) * last.bpm / 60.0;
+        }
+
+        let (segment, from) = match *maybe_last {
+            Some(position) if position.secs <= secs => {
+                let mut i = position.segment;
+                while self.marks[i + 1].secs <= secs {
+                    i += 1;
+                }
+                if i == position.segment {
+                    (i, (position.secs, position.beat))
+                } else {
+                    (i, (self.marks[i].secs, self.marks[i].beat))
+                }
+            },
+            _ => {
+                let i = self.marks.partition_point(|mark| mark.secs <= secs) - 1;
+                (i, (self.marks[i].secs, self.marks[i].beat))
+            },
+        };
+        let beat = self.segment_beat_at(segment, from, secs);
+        *maybe_last = Some(Position { segment, secs, beat });
+        beat
+    }
+
+    /// The beat reached `secs` seconds after the first point, where `secs` lies within the `i`th
+    /// segment at or after the `(secs, beat)` position `from`.
+    fn segment_beat_at(&self, i: usize, from: (f64, f64), secs: f64) -> f64 {
+        let (start, end) = (self.marks[i], self.marks[i + 1]);
+        let (from_secs, from_beat) = from;
+        if secs == from_secs {
+            return from_beat;
+        }
+        let width = end.beat - start.beat;
+        match self.pieces(i) {
+            Some(pieces) => {
+                let t = pieces.t_at((secs - start.secs) / (60.0 * width));
+                start.beat + width * t.clamp(0.0, 1.0)
+            },
+            // Solve forwards from `from`, whose slope is `60 / bpm`.
+            None => {
+                let target = secs - from_secs;
+                let f_df = |beat| {
+                    let secs = self.numeric_secs(i, from_beat, beat);
+                    (secs - target, 60.0 / self.segment_bpm(i, beat))
+                };
+                let tolerance = 4.0 * f64::EPSILON * secs.abs().max(1.0);
+                numeric::newton_bisect(f_df, from_beat, end.beat, tolerance)
+            },
+        }
+    }
+
+    /// The seconds elapsed from the first point to the given beat.
+    fn secs_since_first(&self, beat: f64) -> f64 {
+        let first = self.marks[0];
+        let last = self.marks[self.marks.len() - 1];
+        if beat <= first.beat {
+            (beat - first.beat) * 60.0 / first.bpm
+        } else if beat >= last.beat {
+            last.secs + (beat - last.beat) * 60.0 / last.bpm
+        } else {
+            let i = self.marks.partition_point(|mark| mark.beat <= beat) - 1;
+            self.marks[i].secs + self.segment_secs(i, beat)
+        }
+    }
+
+    /// The tempo at the given beat within the segment starting at the `i`th point.
+    fn segment_bpm(&self, i: usize, beat: f64) -> f64 {
+        let (start, end) = (self.marks[i], self.marks[i + 1]);
+        match self.shapes[i] {
+            Some(shape) => shape.y((start.beat, start.bpm), (end.beat, end.bpm), beat),
+            None => {
+                let maybe_prev = i.checked_sub(1).map(|i| self.points[i]);
+                let maybe_next = self.points.get(i + 2).cloned();
+                scalar(Breakpoint::interpolate_segment(scalar(beat), maybe_prev, self.points[i],
+                                                       self.points[i + 1], maybe_next))
+            },
+        }
+    }
+
+    /// The seconds elapsed from the `i`th point to the given beat within the following segment.
+    fn segment_secs(&self, i: usize, beat: f64) -> f64 {
+        let (start, end) = (self.marks[i], self.marks[i + 1]);
+        let width = end.beat - start.beat;
+        if width <= 0.0 || beat <= start.beat {
+            return 0.0;
+        }
+        match self.pieces(i) {
+            Some(pieces) => 60.0 * width * pieces.minutes(((beat - start.beat) / width).min(1.0)),
+            None => self.numeric_secs(i, start.beat, beat.min(end.beat)),
+        }
+    }
+
+    /// The seconds elapsed between two beats within the `i`th segment, integrated numerically.
+    fn numeric_secs(&self, i: usize, from: f64, to: f64) -> f64 {
+        let (t0, t1) = (self.marks[i].bpm, self.marks[i + 1].bpm);
+        let tolerance = f64::EPSILON * 60.0 * (to - from).abs() / t0.min(t1);
+        numeric::integrate(|beat| 60.0 / self.segment_bpm(i, beat), from, to, tolerance)
+    }
+
+    /// The tempo over the `i`th segment as closed-form pieces, if its shape allows.
+    ///
+    /// Without a known shape, even a segment between equal tempos may bend between them.
+    fn pieces(&self, i: usize) -> Option<Pieces> {
+        let (t0, t1) = (self.marks[i].bpm, self.marks[i + 1].bpm);
+        let diff = t1 - t0;
+        let shape = self.shapes[i]?;
+        let whole = |tempo| Pieces { first: tempo, maybe_second: None };
+        let halves = |first, second| Pieces { first, maybe_second: Some((0.5, second)) };
+        let pieces = match shape {
+            _ if diff == 0.0 => whole(Tempo::Constant(t0)),
+            SegmentShape::Linear => whole(Tempo::Quadratic(0.0, diff, t0)),
+            SegmentShape::Step(jump) => Pieces {
+                first: Tempo::Constant(t0),
+                maybe_second: Some((jump.fraction(), Tempo::Constant(t1))),
+            },
+            SegmentShape::Exponential(k) if k.abs() <= f64::EPSILON => {
+                whole(Tempo::Quadratic(0.0, diff, t0))
+            },
+            SegmentShape::Exponential(k) => {
+                let b = diff / k.exp_m1();
+                whole(Tempo::Exponential(t0 - b, b, k))
+            },
+            SegmentShape::Cosine => whole(Tempo::Cosine(t0, t1)),
+            SegmentShape::Bezier(curve) => {
+                let (a, b) = shape::bezier_coefficients(diff, curve);
+                whole(Tempo::Quadratic(a, b, t0))
+            },
+            SegmentShape::Ease(ease_fn) => {
+                // `2^(n * u)` as an exponential in base `e`.
+                let (k, k2) = (10.0 * LN_2, 20.0 * LN_2);
+                match ease_fn {
+                    EaseFunction::QuadraticIn => whole(Tempo::Quadratic(diff, 0.0, t0)),
+                    EaseFunction::QuadraticOut => whole(Tempo::Quadratic(-diff, 2.0 * diff, t0)),
+                    EaseFunction::QuadraticInOut => halves(
+                        Tempo::Quadratic(2.0 * diff, 0.0, t0),
+                        Tempo::Quadratic(-2.0 * diff, 2.0 * diff, t0 + 0.5 * diff),
+                    ),
+                    EaseFunction::SineInOut => whole(Tempo::Cosine(t0, t1)),
+                    EaseFunction::ExponentialIn => {
+                        whole(Tempo::Exponential(t0, diff * (-k).exp(), k))
+                    },
+                    EaseFunction::ExponentialOut => whole(Tempo::Exponential(t1, -diff, -k)),
+                    EaseFunction::ExponentialInOut => halves(
+                        Tempo::Exponential(t0, 0.5 * diff * (-0.5 * k2).exp(), k2),
+                        Tempo::Exponential(t1, -0.5 * diff, -k2),
+                    ),
+                    _ => return None,
+                }
+            },
+        };
+        Some(pieces)
+    }
+}
+
+
+impl<'a, 'b, E, T> Iterator for BeatSteps<'a, 'b, E, T>
+    where 'a: 'b,
+          E: Envelope<'b>,
+          E::X: NumCast,
+          T: Envelope<'a>,
+          T::X: NumCast,
+          T::Y: NumCast + Spatial,
+          <T::Y as Spatial>::Scalar: Float + Ease,
+          T::Point: Point<X=T::X, Y=T::Y>,
+{
+    type Item = E::Y;
+    #[inline]
+    fn next(&mut self) -> Option<E::Y> {
+        let secs = self.start + self.step * self.n as f64;
+        self.n += 1;
+        let beat = self.tempo.beat_from(&mut self.maybe_last, secs);
+        self.cursor.y_at(scalar(beat))
+    }
+}
+
+
+impl Tempo {
+    /// The integral of `1 / bpm` from zero to `u`, i.e. the minutes elapsed per beat of width.
+    fn minutes(&self, u: f64) -> f64 {
+        match *self {
+            Tempo::Constant(bpm) => u / bpm,
+            Tempo::Quadratic(a, b, c) => quadratic_reciprocal_integral(a, b, c, u),
+            Tempo::Exponential(a, b, k) => exponential_reciprocal_integral(a, b, k, u),
+            Tempo::Cosine(t0, t1) => {
+                // The tempo is `m - r * cos(pi * u)` where `m^2 - r^2 == t0 * t1`.
+                let half_angle = u * PI / 2.0;
+                let angle = (t1.sqrt() * half_angle.sin()).atan2(t0.sqrt() * half_angle.cos());
+                2.0 * angle / (PI * (t0 * t1).sqrt())
+            },
+        }
+    }
+
+    /// The `u` at which the given `minutes` have elapsed, i.e. the inverse of `minutes`.
+    fn u_at(&self, minutes: f64) -> f64 {
+        match *self {
+            Tempo::Constant(bpm) => minutes * bpm,
+            Tempo::Quadratic(a, b, c) => quadratic_reciprocal_integral_inverse(a, b, c, minutes),
+            Tempo::Exponential(a, b, k) => {
+                exponential_reciprocal_integral_inverse(a, b, k, minutes)
+            },
+            Tempo::Cosine(t0, t1) => {
+                let angle = minutes * PI * (t0 * t1).sqrt() / 2.0;
+                let half_angle = (t0.sqrt() * angle.sin()).atan2(t1.sqrt() * angle.cos());
+                2.0 * half_angle / PI
+            },
+        }
+    }
+}
+
+impl Pieces {
+    /// The minutes elapsed per beat of width from the start of the segment to the fraction `t`.
+    fn minutes(&self, t: f64) -> f64 {
+        match self.maybe_second {
+            Some((split, second)) if t > split => {
+                self.first.minutes(split) + second.minutes(t - split)
+            },
+            _ => self.first.minutes(t),
+        }
+    }
+
+    /// The fraction of the segment at which the given minutes per beat of width have elapsed.
+    fn t_at(&self, minutes: f64) -> f64 {
+        if let Some((split, second)) = self.maybe_second {
+            let at_split = self.first.minutes(split);
+            if minutes > at_split {
+                return split + second.u_at(minutes - at_split);
+            }
+        }
+        self.first.u_at(minutes)
+    }
+}
+
+
+/// The integral of `1 / (c + b * t)` from zero to `t`.
+#[inline]
+fn linear_reciprocal_integral(c: f64, b: f64, t: f64) -> f64 {
+    (b * t / c).ln_1p() / b
+}
+
+/// The `t` at which `linear_reciprocal_integral(c, b, t)` reaches `m`.
+#[inline]
+fn linear_reciprocal_integral_inverse(c: f64, b: f64, m: f64) -> f64 {
+    c * (m * b).exp_m1() / b
+}
+
+/// The integral of `1 / (a * t^2 + b * t + c)` from zero to `t`, where the quadratic has no roots
+/// within that range.
+fn quadratic_reciprocal_integral(a: f64, b: f64, c: f64, t: f64) -> f64 {
+    if a.abs() <= f64::EPSILON * (b.abs() + c.abs()) {
+        return if b == 0.0 { t / c } else { linear_reciprocal_integral(c, b, t) };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    let (u0, u1) = (b, 2.0 * a * t + b);
+    if discriminant < 0.0 {
+        let sqrt = (-discriminant).sqrt();
+        2.0 * ((u1 / sqrt).atan() - (u0 / sqrt).atan()) / sqrt
+    } else if discriminant > 0.0 {
+        let sqrt = discriminant.sqrt();
+        (((u1 - sqrt) * (u0 + sqrt)) / ((u1 + sqrt) * (u0 - sqrt))).ln() / sqrt
+    } else {
+        2.0 / u0 - 2.0 / u1
+    }
+}
+
+/// The `t` at which `quadratic_reciprocal_integral(a, b, c, t)` reaches `m`.
+fn quadratic_reciprocal_integral_inverse(a: f64, b: f64, c: f64, m: f64) -> f64 {
+    if a.abs() <= f64::EPSILON * (b.abs() + c.abs()) {
+        return if b == 0.0 { m * c } else { linear_reciprocal_integral_inverse(c, b, m) };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    let u0 = b;
+    let u1 = if discriminant < 0.0 {
+        let sqrt = (-discriminant).sqrt();
+        sqrt * (m * sqrt / 2.0 + (u0 / sqrt).atan()).tan()
+    } else if discriminant > 0.0 {
+        let sqrt = discriminant.sqrt();
+        let ratio = (m * sqrt).exp() * (u0 - sqrt) / (u0 + sqrt);
+        sqrt * (1.0 + ratio) / (1.0 - ratio)
+    } else {
+        2.0 * u0 / (2.0 - m * u0)
+    };
+    (u1 - b) / (2.0 * a)
+}
+
+/// The integral of `1 / (a + b * e^(k * t))` from zero to `t`.
+fn exponential_reciprocal_integral(a: f64, b: f64, k: f64, t: f64) -> f64 {
+    if a == 0.0 {
+        -(-k * t).exp_m1() / (b * k)
+    } else {
+        // Equal to `(t - ln(bpm / (a + b)) / k) / a`, but without cancellation as `a` nears zero.
+        -(a * (-k * t).exp_m1() / (a + b)).ln_1p() / (k * a)
+    }
+}
+
+/// The `t` at which `exponential_reciprocal_integral(a, b, k, t)` reaches `m`.
+fn exponential_reciprocal_integral_inverse(a: f64, b: f64, k: f64, m: f64) -> f64 {
+    let exp_m1 = if a == 0.0 { -m * b * k } else { (-k * a * m).exp_m1() * (a + b) / a };
+    -exp_m1.ln_1p() / k
+}
+
+/// Convert the scalars of the given shape to `f64`.
+fn to_f64<S>(shape: SegmentShape<S>) -> SegmentShape<f64>
+    where S: NumCast,
+{
+    match shape {
+        SegmentShape::Linear => SegmentShape::Linear,
+        SegmentShape::Ease(ease_fn) => SegmentShape::Ease(ease_fn),
+        SegmentShape::Bezier(curve) => SegmentShape::Bezier(scalar(curve)),
+        SegmentShape::Step(jump) => SegmentShape::Step(jump),
+        SegmentShape::Exponential(k) => SegmentShape::Exponential(scalar(k)),
+        SegmentShape::Cosine => SegmentShape::Cosine,
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+
+/// The units in which an envelope's `X` measures time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    Seconds,
+    Milliseconds,
+}
+
+/// Types that may describe a position in time along an envelope's `X` axis.
+pub trait Time: Sized {
+    /// The position lying the given number of seconds along the axis, measured in `unit`.
+    fn from_secs(secs: f64, unit: TimeUnit) -> Self;
+    /// The number of seconds from the start of the axis, where `self` is measured in `unit`.
+    fn to_secs(&self, unit: TimeUnit) -> f64;
+}
+
+/// Maps between sample indices and positions along an envelope's `X` axis for some sample rate.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SampleClock {
+    sample_rate: f64,
+    unit: TimeUnit,
+}
+
+
+impl TimeUnit {
+    /// The number of this unit within a second.
+    #[inline]
+    pub fn per_sec(&self) -> f64 {
+        match *self {
+            TimeUnit::Seconds => 1.0,
+            TimeUnit::Milliseconds => 1_000.0,
+        }
+    }
+}
+
+
+macro_rules! impl_time_for_float {
+    ($($T:ty)*) => {
+        $(
+            impl Time for $T {
+                #[inline]
+                fn from_secs(secs: f64, unit: TimeUnit) -> Self {
+                    (secs * unit.per_sec()) as $T
+                }
+                #[inline]
+                fn to_secs(&self, unit: TimeUnit) -> f64 {
+                    *self as f64 / unit.per_sec()
+                }
+            }
+        )*
+    };
+}
+
+impl_time_for_float!(f32 f64);
+
+/// A `Duration` carries its own units, so the `TimeUnit` is ignored.
+impl Time for Duration {
+    #[inline]
+    fn from_secs(secs: f64, _unit: TimeUnit) -> Self {
+        Duration::from_secs_f64(secs)
+    }
+    #[inline]
+    fn to_secs(&self, _unit: TimeUnit) -> f64 {
+        self.as_secs_f64()
+    }
+}
+
+
+impl SampleClock {
+    /// A clock running at the given number of samples per second, for envelopes whose `X` is
+    /// measured in the given unit.
+    #[inline]
+    pub fn new(sample_rate: f64, unit: TimeUnit) -> Self {
+        SampleClock {
+            sample_rate,
+            unit,
+        }
+    }
+
+    /// A clock for envelopes whose `X` is measured in seconds.
+    #[inline]
+    pub fn seconds(sample_rate: f64) -> Self {
+        SampleClock::new(sample_rate, TimeUnit::Seconds)
+    }
+
+    /// A clock for envelopes whose `X` is measured in milliseconds.
+    #[inline]
+    pub fn milliseconds(sample_rate: f64) -> Self {
+        SampleClock::new(sample_rate, TimeUnit::Milliseconds)
+    }
+
+    /// The number of samples per second.
+    #[inline]
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// The units in which the envelope's `X` is measured.
+    #[inline]
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+
+    /// The position of the sample with the given index.
+    ///
+    /// The position is calculated directly from the index, so no error accumulates over long
+    /// runs of samples.
+    #[inline]
+    pub fn x_at<X>(&self, sample: u64) -> X
+        where X: Time,
+    {
+        X::from_secs(sample as f64 / self.sample_rate, self.unit)
+    }
+
+    /// The fractional sample index at which the given position lies.
+    #[inline]
+    pub fn sample_at<X>(&self, x: &X) -> f64
+        where X: Time,
+    {
+        x.to_secs(self.unit) * self.sample_rate
+    }
+}
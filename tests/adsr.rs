@@ -0,0 +1,149 @@
+extern crate envelope;
+
+use envelope::{Adsr, AdsrStage, Ramp, SegmentShape};
+use envelope::interpolation::EaseFunction;
+
+fn assert_levels(adsr: &mut Adsr, expected: &[f32]) {
+    let levels: Vec<f32> = adsr.take(expected.len()).collect();
+    for (i, (&level, &expected)) in levels.iter().zip(expected).enumerate() {
+        assert!((level - expected).abs() < 1e-6, "sample {}: {:?} != {:?}", i, levels, expected);
+    }
+}
+
+#[test]
+fn adsr_stages() {
+    // At 10 Hz each sample is 0.1 seconds.
+    let mut adsr = Adsr::new(10.0, Ramp::linear(0.4), Ramp::linear(0.2), 0.5, Ramp::linear(0.5));
+    assert_eq!(adsr.stage(), AdsrStage::Idle);
+    assert_levels(&mut adsr, &[0.0, 0.0]);
+
+    adsr.gate_on();
+    assert_levels(&mut adsr, &[0.0, 0.25, 0.5, 0.75, 1.0, 0.75, 0.5, 0.5]);
+    assert_eq!(adsr.stage(), AdsrStage::Sustain);
+
+    adsr.gate_off();
+    assert_levels(&mut adsr, &[0.5, 0.4, 0.3, 0.2, 0.1, 0.0, 0.0]);
+    assert_eq!(adsr.stage(), AdsrStage::Idle);
+    assert!(!adsr.is_active());
+}
+
+#[test]
+fn delay_and_hold() {
+    let mut adsr = Adsr::dahdsr(10.0, 0.2, Ramp::linear(0.2), 0.3, Ramp::linear(0.1), 0.25,
+                                Ramp::linear(0.1));
+    adsr.gate_on();
+    assert_levels(&mut adsr, &[0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0, 0.25, 0.25]);
+
+    let mut ahdsr = Adsr::ahdsr(10.0, Ramp::linear(0.2), 0.3, Ramp::linear(0.1), 0.25,
+                                Ramp::linear(0.1));
+    ahdsr.gate_on();
+    assert_levels(&mut ahdsr, &[0.0, 0.5, 1.0, 1.0, 1.0, 1.0, 0.25, 0.25]);
+}
+
+#[test]
+fn gates_continue_from_the_current_level() {
+    let mut adsr = Adsr::new(10.0, Ramp::linear(1.0), Ramp::linear(0.1), 0.5, Ramp::linear(0.3));
+    adsr.gate_on();
+    assert_levels(&mut adsr, &[0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+
+    // Releasing during the attack falls from the current level rather than the sustain level.
+    adsr.gate_off();
+    assert_levels(&mut adsr, &[0.6, 0.4, 0.2]);
+
+    // Re-opening the gate during the release attacks from the current level.
+    adsr.gate_on();
+    assert_levels(&mut adsr, &[0.2, 0.28, 0.36]);
+    assert_eq!(adsr.stage(), AdsrStage::Attack);
+}
+
+#[test]
+fn fill_matches_next_and_shapes_apply() {
+    let attack = Ramp::new(0.5, SegmentShape::Ease(EaseFunction::QuadraticIn));
+    let mut a = Adsr::new(48_000.0, attack, Ramp::linear(0.1), 0.7, Ramp::linear(0.2));
+    let mut b = a.clone();
+    a.gate_on();
+    b.gate_on();
+
+    let mut buffer = [0.0; 64];
+    a.fill(&mut buffer);
+    let expected: Vec<f32> = b.by_ref().take(64).collect();
+    assert_eq!(&buffer[..], &expected[..]);
+
+    // Halfway through the attack a quadratic ease-in reaches a quarter of the peak.
+    let mut halfway = Adsr::new(10.0, attack, Ramp::linear(0.1), 0.7, Ramp::linear(0.2));
+    halfway.gate_on();
+    assert!((halfway.nth(2).unwrap() - 0.16).abs() < 1e-6);
+}
+
+#[test]
+fn curve_matches_the_generated_levels() {
+    use envelope::Envelope;
+
+    let attack = Ramp::new(0.3, SegmentShape::Ease(EaseFunction::QuadraticOut));
+    let decay = Ramp::new(0.2, SegmentShape::Exponential(-2.0));
+    let release = Ramp::new(0.4, SegmentShape::Bezier(0.5));
+    let sample_rate = 100.0;
+    for &(delay, hold) in &[(0.0, 0.0), (0.1, 0.0), (0.05, 0.25)] {
+        let mut adsr = Adsr::dahdsr(sample_rate, delay, attack, hold, decay, 0.6, release);
+        let curve = adsr.curve(0.5);
+        let gate_samples = ((delay + 0.3 + hold + 0.2 + 0.5) * sample_rate).round() as usize;
+
+        adsr.gate_on();
+        let mut levels: Vec<f32> = adsr.by_ref().take(gate_samples).collect();
+        adsr.gate_off();
+        levels.extend(adsr.take(60));
+        for (i, level) in levels.into_iter().enumerate() {
+            let y = curve.y(i as f32 / sample_rate).unwrap_or(0.0);
+            assert!((y - level).abs() < 1e-3, "{:?}: sample {}: {} != {}", (delay, hold), i, y,
+                    level);
+        }
+    }
+}
+
+#[test]
+fn curve_jumps_over_stages_with_no_duration() {
+    use envelope::{AnyPoint, Envelope, VecEnvelope};
+
+    // The level of the curve at `x`, taking the level after any jump that lands on `x` as the
+    // generator does.
+    fn level_at(curve: &VecEnvelope<AnyPoint<f32, f32>>, x: f32) -> f32 {
+        match curve.as_slice().iter().rev().find(|p| p.x == x) {
+            Some(p) => p.y,
+            None => curve.y(x).unwrap_or(0.0),
+        }
+    }
+
+    let sample_rate = 100.0;
+    let ramp = Ramp::linear(0.5);
+    let no_ramp = Ramp::linear(0.0);
+    let adsrs = [
+        Adsr::dahdsr(sample_rate, 1.0, no_ramp, 0.0, ramp, 0.5, ramp),
+        Adsr::dahdsr(sample_rate, 0.0, ramp, 0.0, ramp, 0.5, no_ramp),
+        Adsr::dahdsr(sample_rate, 0.0, no_ramp, 0.25, no_ramp, 0.5, no_ramp),
+        Adsr::dahdsr(sample_rate, 0.5, ramp, 0.25, no_ramp, 0.5, ramp),
+    ];
+    for (n, adsr) in adsrs.iter().enumerate() {
+        let mut adsr = adsr.clone();
+        let curve = adsr.curve(1.0);
+        let gate_duration = adsr.delay + adsr.attack.duration + adsr.hold + adsr.decay.duration
+            + 1.0;
+        let mut levels = vec![0.0; (gate_duration * sample_rate).round() as usize];
+        adsr.gate_on();
+        adsr.fill(&mut levels);
+        let mut release = vec![0.0; 100];
+        adsr.gate_off();
+        adsr.fill(&mut release);
+        levels.extend(release);
+        for (i, level) in levels.into_iter().enumerate() {
+            let y = level_at(&curve, i as f32 / sample_rate);
+            assert!((y - level).abs() < 1e-3, "adsr {}: sample {}: {} != {}", n, i, y, level);
+        }
+    }
+
+    // The level before a jump is kept.
+    let delayed = Adsr::dahdsr(sample_rate, 1.0, no_ramp, 0.0, ramp, 0.5, ramp).curve(1.0);
+    assert_eq!(delayed.y(0.5), Some(0.0));
+    let held = Adsr::new(sample_rate, ramp, ramp, 0.5, no_ramp).curve(1.0);
+    assert_eq!(held.y(1.5), Some(0.5));
+    assert_eq!(level_at(&held, 2.0), 0.0);
+}
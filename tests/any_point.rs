@@ -0,0 +1,64 @@
+extern crate envelope;
+
+use envelope::{AnyPoint, Envelope, SegmentShape, VecEnvelope};
+use envelope::interpolate::Jump;
+use envelope::interpolation::EaseFunction;
+
+fn mixed() -> VecEnvelope<AnyPoint<f64, f64>> {
+    vec![
+        AnyPoint::new(0.0, 0.0, SegmentShape::Step(Jump::At(0.5))),
+        AnyPoint::new(1.0, 1.0, SegmentShape::Linear),
+        AnyPoint::new(2.0, 0.0, SegmentShape::Ease(EaseFunction::QuadraticIn)),
+        AnyPoint::new(3.0, 1.0, SegmentShape::Bezier(0.5)),
+        AnyPoint::new(4.0, 0.0, SegmentShape::Exponential(3.0)),
+        AnyPoint::new(5.0, 1.0, SegmentShape::Cosine),
+        AnyPoint::new(6.0, 0.0, SegmentShape::Linear),
+    ].into()
+}
+
+#[test]
+fn each_segment_uses_its_own_shape() {
+    let env = mixed();
+    let close = |x: f64, expected: f64| {
+        let y = env.y(x).unwrap();
+        assert!((y - expected).abs() < 1e-12, "x = {}: {} != {}", x, y, expected);
+    };
+    close(0.25, 0.0);
+    close(0.75, 1.0);
+    close(1.5, 0.5);
+    close(2.5, 0.25);
+    close(4.5, (1.5f64.exp() - 1.0) / (3.0f64.exp() - 1.0));
+    close(5.25, 1.0 - (1.0 - (0.25 * std::f64::consts::PI).cos()) / 2.0);
+
+    // Each segment agrees with the shape's own evaluation.
+    let points = env.as_slice();
+    for w in points.windows(2) {
+        let (start, end) = ((w[0].x, w[0].y), (w[1].x, w[1].y));
+        for i in 1..10 {
+            let x = start.0 + (end.0 - start.0) * i as f64 / 10.0;
+            assert!((w[0].shape.y(start, end, x) - env.y(x).unwrap()).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn xs_at_y_across_shapes() {
+    let env = mixed();
+    for &y in &[0.2, 0.5, 0.8] {
+        let xs: Vec<f64> = env.xs_at_y(y).collect();
+        // Every segment crosses `y` once.
+        assert_eq!(xs.len(), 6, "y = {}", y);
+        assert_eq!(xs[0], 0.5);
+        for &x in &xs[1..] {
+            assert!((env.y(x).unwrap() - y).abs() < 1e-9, "y = {}, x = {}", y, x);
+        }
+    }
+}
+
+#[test]
+fn debug() {
+    let point = AnyPoint::new(1.0, 0.5, SegmentShape::Ease(EaseFunction::BounceOut));
+    assert_eq!(format!("{:?}", point), "AnyPoint { x: 1.0, y: 0.5, shape: Ease(BounceOut) }");
+    let shape: SegmentShape<f64> = SegmentShape::Step(Jump::At(0.25));
+    assert_eq!(format!("{:?}", shape), "Step(At(0.25))");
+}
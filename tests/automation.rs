@@ -0,0 +1,133 @@
+extern crate envelope;
+
+use envelope::{AutomationMode, AutomationRecorder, EasePoint, Envelope, HermitePoint, VecEnvelope};
+
+type Env = VecEnvelope<EasePoint<f64, f64>>;
+
+/// An existing automation curve holding `0.5` from 0 to 10.
+fn existing() -> Env {
+    vec![(0.0, 0.5), (5.0, 0.5), (10.0, 0.5)].into_iter()
+        .map(|(x, y)| EasePoint::new(x, y, None))
+        .collect()
+}
+
+fn xys(env: &Env) -> Vec<(f64, f64)> {
+    env.as_slice().iter().map(|p| (p.x, p.y)).collect()
+}
+
+#[test]
+fn write() {
+    let mut env = existing();
+    let mut recorder = AutomationRecorder::new(AutomationMode::Write, 1e-9);
+    assert!(!recorder.is_recording());
+    for i in 0..201 {
+        let x = 2.0 + i as f64 / 100.0;
+        recorder.record(x, (x - 2.0) / 2.0);
+    }
+    assert!(recorder.is_recording());
+    recorder.stop(&mut env, 6.0);
+    assert!(!recorder.is_recording());
+
+    // The ramp is thinned to its ends, the last value is held until the recorder is stopped and
+    // the existing point within the recorded range is replaced.
+    let expected = vec![
+        (0.0, 0.5), (2.0, 0.5), (2.0, 0.0), (4.0, 1.0), (6.0, 1.0), (6.0, 0.5), (10.0, 0.5),
+    ];
+    assert_eq!(xys(&env), expected);
+}
+
+#[test]
+fn touch() {
+    let mut env = existing();
+    let mut recorder = AutomationRecorder::new(AutomationMode::Touch, 1e-9);
+
+    // Values are ignored until the control is touched.
+    recorder.record(1.0, 0.0);
+    assert!(!recorder.is_recording());
+
+    recorder.touch();
+    assert!(recorder.is_touched());
+    for i in 0..11 {
+        recorder.record(3.0 + i as f64 / 10.0, 0.8);
+    }
+    // Releasing returns to the existing curve.
+    recorder.release(&mut env, 5.0);
+    assert!(!recorder.is_recording());
+    recorder.record(6.0, 0.1);
+    assert!(!recorder.is_recording());
+
+    let expected = vec![(0.0, 0.5), (3.0, 0.5), (3.0, 0.8), (4.0, 0.8), (5.0, 0.5), (10.0, 0.5)];
+    assert_eq!(xys(&env), expected);
+    assert_eq!(env.y(4.5), Some(0.65));
+
+    // A second pass replaces the points of the first within its range, returning to what remains
+    // of it.
+    recorder.touch();
+    recorder.record(3.0, 0.2);
+    recorder.record(4.0, 0.2);
+    recorder.release(&mut env, 4.0);
+    recorder.stop(&mut env, 8.0);
+    let expected = vec![
+        (0.0, 0.5), (3.0, 0.5), (3.0, 0.2), (4.0, 0.2), (4.0, 0.8), (5.0, 0.5), (10.0, 0.5),
+    ];
+    assert_eq!(xys(&env), expected);
+}
+
+#[test]
+fn latch() {
+    let mut env = existing();
+    let mut recorder = AutomationRecorder::new(AutomationMode::Latch, 1e-9);
+    recorder.record(1.0, 0.0);
+    assert!(!recorder.is_recording());
+
+    recorder.touch();
+    recorder.record(3.0, 0.0);
+    recorder.record(4.0, 1.0);
+    recorder.release(&mut env, 4.0);
+    // The last value is held after the release.
+    assert!(recorder.is_recording());
+    assert_eq!(xys(&env), xys(&existing()));
+
+    // Values that arrive after the release are ignored in favour of the last touched value.
+    recorder.record(6.0, 0.2);
+    recorder.record(7.0, 0.3);
+    recorder.stop(&mut env, 8.0);
+    let expected = vec![
+        (0.0, 0.5), (3.0, 0.5), (3.0, 0.0), (4.0, 1.0), (8.0, 1.0), (8.0, 0.5), (10.0, 0.5),
+    ];
+    assert_eq!(xys(&env), expected);
+    assert_eq!(env.y(6.0), Some(1.0));
+}
+
+#[test]
+fn recorded_gesture_is_thinned() {
+    let samples: Vec<(f64, f64)> = (0..1_000)
+        .map(|i| i as f64 / 100.0)
+        .map(|x| (x, (x * 1.3).sin() * 0.4 + 0.5))
+        .collect();
+    let tolerance = 0.001;
+
+    let mut env = existing();
+    let mut recorder = AutomationRecorder::new(AutomationMode::Write, tolerance);
+    for &(x, y) in &samples {
+        recorder.record(x, y);
+    }
+    recorder.stop(&mut env, 9.99);
+    assert!(env.as_slice().len() < 150, "{} points", env.as_slice().len());
+    for &(x, y) in &samples {
+        assert!((env.y(x).unwrap() - y).abs() <= tolerance, "at {}", x);
+    }
+
+    // Smooth points need fewer still.
+    let mut hermite: VecEnvelope<HermitePoint<f64, f64>> = VecEnvelope::new();
+    let mut recorder = AutomationRecorder::new(AutomationMode::Write, tolerance);
+    for &(x, y) in &samples {
+        recorder.record(x, y);
+    }
+    recorder.stop(&mut hermite, 9.99);
+    assert!(hermite.as_slice().len() < 50, "{} points", hermite.as_slice().len());
+    // Tangents are refined by the samples that follow each point, so may stray a little.
+    for &(x, y) in &samples {
+        assert!((hermite.y(x).unwrap() - y).abs() <= 2.0 * tolerance, "at {}", x);
+    }
+}
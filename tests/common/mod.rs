@@ -0,0 +1,81 @@
+//! Fixtures shared between the integration tests.
+
+// Each test crate uses only some of the fixtures.
+#![allow(dead_code)]
+
+use envelope::{Envelope, Point};
+use envelope::interpolation::Spatial;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct P {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point for P {
+    type X = f64;
+    type Y = f64;
+    fn x_to_scalar(x: f64) -> <Self::Y as Spatial>::Scalar { x }
+    fn x(&self) -> f64 { self.x }
+    fn y(&self) -> f64 { self.y }
+}
+
+/// Only exposes its points via an iterator, so all queries fall back to a linear scan.
+pub struct Linear(pub Vec<P>);
+
+/// Exposes its points as a slice, so all queries use a binary search.
+pub struct Sorted(pub Vec<P>);
+
+impl<'a> Envelope<'a> for Linear {
+    type X = f64;
+    type Y = f64;
+    type Point = P;
+    type Points = ::std::slice::Iter<'a, P>;
+    fn points(&'a self) -> Self::Points { self.0.iter() }
+}
+
+impl<'a> Envelope<'a> for Sorted {
+    type X = f64;
+    type Y = f64;
+    type Point = P;
+    type Points = ::std::slice::Iter<'a, P>;
+    fn points(&'a self) -> Self::Points { self.0.iter() }
+    fn points_slice(&'a self) -> Option<&'a [P]> { Some(&self.0) }
+}
+
+
+/// Integrate `f` by the midpoint rule, with `1 / 2^14` between samples.
+pub fn sampled_integral<F>(f: F, x0: f64, x1: f64) -> f64
+    where F: Fn(f64) -> f64,
+{
+    let width = 1.0 / 16_384.0;
+    let n = ((x1 - x0) / width) as usize;
+    (0..n).map(|i| f(x0 + (i as f64 + 0.5) * width) * width).sum()
+}
+
+/// The slope of the envelope at `x` by the central difference.
+pub fn sampled_slope<'a, E>(env: &'a E, x: f64) -> f64
+    where E: Envelope<'a, X=f64, Y=f64>,
+{
+    let h = 1e-6;
+    (env.y(x + h).unwrap() - env.y(x - h).unwrap()) / (2.0 * h)
+}
+
+/// The range of the envelope found by densely sampling it.
+pub fn sampled_range<'a, E>(env: &'a E, x0: f64, x1: f64) -> (f64, f64)
+    where E: Envelope<'a, X=f64, Y=f64>,
+{
+    let n = 100_000;
+    (0..n + 1)
+        .map(|i| env.y(x0 + (x1 - x0) * i as f64 / n as f64).unwrap())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), y| (min.min(y), max.max(y)))
+}
+
+/// Count the crossings of `y` by densely sampling the envelope.
+pub fn sampled_crossings<'a, E>(env: &'a E, y: f64, start: f64, end: f64) -> usize
+    where E: Envelope<'a, X=f64, Y=f64>,
+{
+    let n = 100_000;
+    let sample = |i: usize| env.y(start + (end - start) * i as f64 / n as f64).unwrap() - y;
+    (0..n).filter(|&i| (sample(i) < 0.0) != (sample(i + 1) < 0.0)).count()
+}
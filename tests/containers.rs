@@ -0,0 +1,116 @@
+extern crate envelope;
+
+use envelope::{ArrayEnvelope, ArrayEnvelopeError, BezierPoint, CapacityError, Envelope, EnvelopeMut,
+               SliceEnvelope, UnsortedError, VecEnvelope};
+use std::convert::TryFrom;
+
+type P = BezierPoint<f64, f64>;
+
+fn p(x: f64, y: f64) -> P {
+    BezierPoint::new(x, y, 0.0)
+}
+
+fn unsorted() -> Vec<P> {
+    vec![p(10.0, 1.0), p(0.0, 0.0), p(5.0, 0.5), p(5.0, 0.25)]
+}
+
+fn sorted() -> Vec<P> {
+    vec![p(0.0, 0.0), p(5.0, 0.5), p(5.0, 0.25), p(10.0, 1.0)]
+}
+
+#[test]
+fn vec_envelope() {
+    assert_eq!(VecEnvelope::from_sorted(unsorted()), Err(UnsortedError { idx: 1 }));
+    let env = VecEnvelope::from_sorted(sorted()).unwrap();
+    assert_eq!(env, VecEnvelope::from(unsorted()));
+    assert_eq!(env, unsorted().into_iter().collect::<VecEnvelope<P>>());
+    assert_eq!(env.y(2.5), Some(0.25));
+    assert_eq!(env.y(7.5), Some(0.625));
+
+    let mut extended = VecEnvelope::from(vec![p(5.0, 0.5), p(10.0, 1.0)]);
+    extended.extend(vec![p(5.0, 0.25), p(0.0, 0.0)]);
+    assert_eq!(extended, env);
+
+    let mut edited = env.clone();
+    assert_eq!(edited.insert_point(p(2.0, 0.1)), 1);
+    assert_eq!(edited.move_point(1, 7.0, 0.1), Some(3));
+    assert_eq!(edited.as_slice()[3], p(7.0, 0.1));
+    assert_eq!(edited.into_vec().len(), 5);
+}
+
+#[test]
+fn slice_envelope() {
+    let unsorted = unsorted();
+    let sorted = sorted();
+    assert_eq!(SliceEnvelope::try_from(&unsorted), Err(UnsortedError { idx: 1 }));
+    let env = SliceEnvelope::try_from(&sorted[..]).unwrap();
+    assert_eq!(env.as_slice(), &sorted[..]);
+    assert_eq!(env.y(2.5), Some(0.25));
+    assert_eq!(env.point_idx_on_or_before(5.0), Some(2));
+}
+
+#[test]
+fn array_envelope() {
+    assert_eq!(ArrayEnvelope::<P, 4>::from_sorted(unsorted()),
+               Err(ArrayEnvelopeError::Unsorted(UnsortedError { idx: 1 })));
+    assert_eq!(ArrayEnvelope::<P, 3>::from_sorted(sorted()),
+               Err(ArrayEnvelopeError::Capacity(CapacityError { capacity: 3 })));
+    let env: ArrayEnvelope<P, 4> = ArrayEnvelope::from_sorted(sorted()).unwrap();
+    assert!(env.is_full());
+    assert_eq!(env.capacity(), 4);
+    assert_eq!(Ok(env.clone()), ArrayEnvelope::try_from(unsorted()));
+    assert_eq!(ArrayEnvelope::<P, 3>::try_from(unsorted()), Err(CapacityError { capacity: 3 }));
+    assert_eq!(env, unsorted().into_iter().collect::<ArrayEnvelope<P, 4>>());
+    assert_eq!(env.y(2.5), Some(0.25));
+    assert_eq!(env.y(7.5), Some(0.625));
+
+    let mut edited = env.clone();
+    assert_eq!(edited.try_insert_point(p(1.0, 1.0)), Err(CapacityError { capacity: 4 }));
+    assert_eq!(edited.remove_point(0), Some(p(0.0, 0.0)));
+    assert_eq!(edited.as_slice(), &sorted()[1..]);
+    assert_eq!(edited.insert_point(p(7.0, 0.0)), 2);
+    assert_eq!(edited.move_point(2, 0.0, 0.0), Some(0));
+    assert_eq!(edited, env);
+
+    // Moving a point within a vertical jump without changing its `x` keeps the jump's direction.
+    let mut jump = env.clone();
+    assert_eq!(jump.move_point(1, 5.0, 0.75), Some(1));
+    assert_eq!(&jump.as_slice()[1..3], &[p(5.0, 0.75), p(5.0, 0.25)]);
+
+    EnvelopeMut::retain(&mut edited, |p| p.x == 5.0);
+    assert_eq!(edited.as_slice(), &[p(5.0, 0.5), p(5.0, 0.25)]);
+    assert_eq!(edited.len(), 2);
+
+    let empty: ArrayEnvelope<P, 2> = ArrayEnvelope::new();
+    assert!(empty.is_empty());
+    assert_eq!(empty.y(0.0), None);
+}
+
+#[test]
+fn nan_xs_are_sorted_last() {
+    let nan = p(f64::NAN, 0.5);
+    let env = VecEnvelope::from(vec![p(5.0, 0.5), nan, p(0.0, 0.0), p(10.0, 1.0)]);
+    let xs: Vec<f64> = env.as_slice().iter().map(|p| p.x).collect();
+    assert_eq!(&xs[..3], &[0.0, 5.0, 10.0]);
+    assert!(xs[3].is_nan());
+
+    assert_eq!(VecEnvelope::from_sorted(vec![p(0.0, 0.0), nan, p(10.0, 1.0)]),
+               Err(UnsortedError { idx: 2 }));
+    assert!(VecEnvelope::from_sorted(vec![p(0.0, 0.0), p(10.0, 1.0), nan]).is_ok());
+
+    // Inserting places a NaN `x` last too, so the points remain sorted.
+    let mut inserted = VecEnvelope::from(vec![p(0.0, 0.0), p(10.0, 1.0)]);
+    assert_eq!(inserted.insert_point(nan), 2);
+    assert_eq!(inserted.insert_point(p(5.0, 0.5)), 1);
+    assert!(VecEnvelope::from_sorted(inserted.into_vec()).is_ok());
+    let mut array: ArrayEnvelope<P, 4> = ArrayEnvelope::new();
+    assert_eq!(array.try_insert_point(p(5.0, 0.5)), Ok(0));
+    assert_eq!(array.try_insert_point(nan), Ok(1));
+    assert_eq!(array.try_insert_point(p(10.0, 1.0)), Ok(1));
+}
+
+#[test]
+#[should_panic]
+fn array_envelope_over_capacity() {
+    let _: ArrayEnvelope<P, 3> = unsorted().into_iter().collect();
+}
@@ -0,0 +1,63 @@
+extern crate envelope;
+
+use envelope::{CubicBezierPoint, Envelope, VecEnvelope};
+
+type Env = VecEnvelope<CubicBezierPoint<f64, f64>>;
+
+/// Evaluate the cubic bezier with the given control points at `t`.
+fn bezier(p: [(f64, f64); 4], t: f64) -> (f64, f64) {
+    let u = 1.0 - t;
+    let f = |a: f64, b: f64, c: f64, d: f64| {
+        u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+    };
+    (f(p[0].0, p[1].0, p[2].0, p[3].0), f(p[0].1, p[1].1, p[2].1, p[3].1))
+}
+
+#[test]
+fn matches_the_parametric_curve() {
+    // The CSS `ease` timing function, scaled to a segment from (2, 1) to (6, -1).
+    let env: Env = vec![
+        CubicBezierPoint::new(2.0, 1.0, (0.0, 0.0), (1.0, -0.2)),
+        CubicBezierPoint::new(6.0, -1.0, (-3.0, 0.0), (0.0, 0.0)),
+    ].into();
+    let controls = [(2.0, 1.0), (3.0, 0.8), (3.0, -1.0), (6.0, -1.0)];
+    for i in 0..=100 {
+        let (x, y) = bezier(controls, i as f64 / 100.0);
+        assert!((env.y(x).unwrap() - y).abs() < 1e-9, "x = {}", x);
+    }
+}
+
+#[test]
+fn handles_shape_the_curve_even_between_equal_ys() {
+    let env: Env = vec![
+        CubicBezierPoint::new(0.0, 0.0, (0.0, 0.0), (0.25, 1.0)),
+        CubicBezierPoint::new(1.0, 0.0, (-0.25, 1.0), (0.0, 0.0)),
+    ].into();
+    assert!((env.y(0.5).unwrap() - 0.75).abs() < 1e-9);
+
+    // Without handles the curve is a straight line.
+    let line: Env = vec![
+        CubicBezierPoint::new(0.0, 0.0, (0.0, 0.0), (0.0, 0.0)),
+        CubicBezierPoint::new(2.0, 1.0, (0.0, 0.0), (0.0, 0.0)),
+    ].into();
+    for &x in &[0.1, 0.5, 1.3, 1.9] {
+        assert!((line.y(x).unwrap() - x / 2.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn handles_are_clamped_to_the_segment() {
+    // A handle extending past the end of the segment would make `x(t)` double back.
+    let overshooting: Env = vec![
+        CubicBezierPoint::new(0.0, 0.0, (0.0, 0.0), (3.0, 0.5)),
+        CubicBezierPoint::new(1.0, 1.0, (-2.0, -0.5), (0.0, 0.0)),
+    ].into();
+    let clamped: Env = vec![
+        CubicBezierPoint::new(0.0, 0.0, (0.0, 0.0), (1.0, 0.5)),
+        CubicBezierPoint::new(1.0, 1.0, (-1.0, -0.5), (0.0, 0.0)),
+    ].into();
+    for i in 0..=50 {
+        let x = i as f64 / 50.0;
+        assert_eq!(overshooting.y(x), clamped.y(x));
+    }
+}
@@ -0,0 +1,58 @@
+extern crate envelope;
+
+mod common;
+
+use common::{Linear, Sorted, P};
+use envelope::Envelope;
+
+fn points() -> Vec<P> {
+    vec![
+        P { x: 0.0, y: 0.0 },
+        P { x: 1.0, y: 1.0 },
+        P { x: 2.5, y: 0.5 },
+        P { x: 2.5, y: 0.0 },
+        P { x: 4.0, y: 1.0 },
+    ]
+}
+
+/// A sequence of positions moving forwards with varying block sizes, then looping backwards.
+fn positions() -> Vec<f64> {
+    vec![-1.0, 0.0, 0.1, 0.35, 1.0, 1.5, 2.5, 2.75, 4.0, 5.0, 0.5, 3.0, 2.5, 0.0, 4.0, 1.25]
+}
+
+#[test]
+fn y_at_matches_y() {
+    let linear = Linear(points());
+    let sorted = Sorted(points());
+    let mut linear_cursor = linear.cursor();
+    let mut sorted_cursor = sorted.cursor();
+    for &x in &positions() {
+        assert_eq!(linear_cursor.y_at(x), linear.y(x), "x = {}", x);
+        assert_eq!(sorted_cursor.y_at(x), sorted.y(x), "x = {}", x);
+        assert_eq!(linear_cursor.segment_idx(), sorted_cursor.segment_idx(), "x = {}", x);
+    }
+}
+
+#[test]
+fn seek_reports_crossed_points() {
+    let sorted = Sorted(points());
+    let mut cursor = sorted.cursor();
+    assert_eq!(cursor.seek(0.5), 0);
+    assert_eq!(cursor.seek(0.9), 0);
+    assert_eq!(cursor.seek(1.5), 1);
+    assert_eq!(cursor.segment(), (Some(&sorted.0[1]), Some(&sorted.0[2])));
+    assert_eq!(cursor.seek(3.0), 2);
+    assert_eq!(cursor.segment_idx(), 3);
+    assert_eq!(cursor.seek(0.5), 3);
+    assert_eq!(cursor.segment_idx(), 0);
+    assert_eq!(cursor.seek(10.0), 4);
+    assert_eq!(cursor.segment(), (Some(&sorted.0[4]), None));
+}
+
+#[test]
+fn empty_envelope() {
+    let empty = Sorted(vec![]);
+    let mut cursor = empty.cursor();
+    assert_eq!(cursor.seek(1.0), 0);
+    assert_eq!(cursor.y_at(1.0), None);
+}
@@ -0,0 +1,83 @@
+extern crate envelope;
+
+mod common;
+
+use common::sampled_slope;
+use envelope::{AnyPoint, EasePoint, Envelope, HermitePoint, SegmentShape, VecEnvelope};
+use envelope::interpolate::Jump;
+use envelope::interpolation::EaseFunction;
+
+#[test]
+fn linear_segments() {
+    let env: VecEnvelope<EasePoint<f64, f64>> =
+        vec![(0.0, 0.0), (2.0, 1.0), (2.0, 3.0), (3.0, 1.0), (3.0, 2.0)]
+            .into_iter()
+            .map(|(x, y)| EasePoint::new(x, y, None))
+            .collect();
+    assert_eq!(env.dy_dx(0.0), Some(0.5));
+    assert_eq!(env.dy_dx(1.0), Some(0.5));
+    // Points use the segment that begins at them, skipping any vertical segments.
+    assert_eq!(env.dy_dx(2.0), Some(-2.0));
+    assert_eq!(env.dy_dx(3.0), Some(-2.0));
+    assert_eq!(env.dy_dx(-1.0), None);
+    assert_eq!(env.dy_dx(3.5), None);
+
+    let single: VecEnvelope<EasePoint<f64, f64>> = vec![EasePoint::new(1.0, 0.5, None)].into();
+    assert_eq!(single.dy_dx(1.0), Some(0.0));
+}
+
+#[test]
+fn every_shape_matches_its_slope() {
+    let env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+        AnyPoint::new(0.0, 0.0, SegmentShape::Step(Jump::At(0.5))),
+        AnyPoint::new(1.0, 1.0, SegmentShape::Linear),
+        AnyPoint::new(2.0, -0.5, SegmentShape::Bezier(0.75)),
+        AnyPoint::new(3.0, 1.0, SegmentShape::Exponential(3.0)),
+        AnyPoint::new(4.0, 0.0, SegmentShape::Cosine),
+        AnyPoint::new(5.0, 1.0, SegmentShape::Ease(EaseFunction::QuadraticOut)),
+        AnyPoint::new(6.0, 0.25, SegmentShape::Ease(EaseFunction::CubicIn)),
+        AnyPoint::new(7.0, 1.0, SegmentShape::Linear),
+    ].into();
+    let slope = env.derivative();
+    for i in 1..70 {
+        let x = i as f64 / 10.0;
+        if i % 10 == 0 || i == 5 {
+            continue;
+        }
+        let expected = sampled_slope(&env, x);
+        let dy_dx = slope.y(x).unwrap();
+        assert!((dy_dx - expected).abs() < 1e-6, "x = {}: {} != {}", x, dy_dx, expected);
+    }
+}
+
+#[test]
+fn derivative_steps() {
+    let env: VecEnvelope<EasePoint<f64, f64>> = vec![(0.0, 0.0), (2.0, 1.0), (3.0, -1.0)]
+        .into_iter()
+        .map(|(x, y)| EasePoint::new(x, y, None))
+        .collect();
+    let slope = env.derivative();
+    let steps: Vec<f64> = slope.steps(0.0, 0.5).collect();
+    assert_eq!(steps, vec![0.5, 0.5, 0.5, 0.5, -2.0, -2.0, -2.0]);
+
+    let mut steps = slope.steps(1.5, 0.5);
+    let mut out = [0.0; 4];
+    assert_eq!(steps.fill(&mut out), 4);
+    assert_eq!(out, [0.5, -2.0, -2.0, -2.0]);
+    assert_eq!(steps.fill(&mut out), 0);
+}
+
+#[test]
+fn unknown_shapes_are_differentiated_numerically() {
+    let env: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 0.0, 2.0),
+        HermitePoint::new(1.0, 1.0, -1.0),
+        HermitePoint::new(3.0, 0.5, 0.0),
+    ].into_iter().collect();
+    assert!((env.dy_dx(0.0).unwrap() - 2.0).abs() < 1e-6);
+    assert!((env.dy_dx(1.0).unwrap() + 1.0).abs() < 1e-6);
+    assert!(env.dy_dx(3.0).unwrap().abs() < 1e-6);
+    for &x in &[0.25, 0.5, 1.5, 2.5] {
+        assert!((env.dy_dx(x).unwrap() - sampled_slope(&env, x)).abs() < 1e-6, "x = {}", x);
+    }
+}
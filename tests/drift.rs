@@ -0,0 +1,55 @@
+extern crate envelope;
+
+use envelope::{EasePoint, Envelope, VecEnvelope};
+
+/// A ramp whose `y` is equal to its `x` over the given duration.
+fn identity(duration: f32) -> VecEnvelope<EasePoint<f32, f32>> {
+    vec![EasePoint::new(0.0, 0.0, None), EasePoint::new(duration, duration, None)]
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn steps_do_not_drift() {
+    let sample_rate = 48_000;
+    let seconds = 120;
+    let env = identity(seconds as f32);
+    let mut steps = env.steps(0.0, 1.0 / sample_rate as f32).unwrap();
+    let n = sample_rate * seconds;
+    assert_eq!(steps.by_ref().take(n).count(), n);
+    let (x, y) = steps.next_xy().unwrap();
+    assert!((x - seconds as f32).abs() < 1e-5, "x = {}", x);
+    assert!((y - seconds as f32).abs() < 1e-5, "y = {}", y);
+
+    // The same holds when filling buffers.
+    let mut steps = env.steps(0.0, 1.0 / sample_rate as f32).unwrap();
+    let mut out = vec![0.0; 512];
+    let mut written = 0;
+    while written + out.len() <= n {
+        written += steps.fill(&mut out);
+    }
+    let last = (written - 1) as f32 / sample_rate as f32;
+    assert!((out[out.len() - 1] - last).abs() < 1e-5, "y = {}", out[out.len() - 1]);
+}
+
+#[test]
+fn sample_steps() {
+    let env = identity(1.0);
+    assert_eq!(env.sample_steps(2, 4.0).collect::<Vec<_>>(), vec![0.5, 0.75, 1.0]);
+
+    let mut samples = env.sample_steps(0, 44_100.0);
+    samples.set_sample(44_099);
+    assert_eq!(samples.x_at(44_100), 1.0);
+    assert_eq!(samples.next(), Some(44_099.0 / 44_100.0));
+    assert_eq!(samples.next(), Some(1.0));
+    assert_eq!(samples.sample(), 44_101);
+    assert_eq!(samples.next(), None);
+
+    // Filling continues from the same sample and stops once out of range.
+    let mut samples = env.sample_steps(1, 4.0);
+    let mut out = [0.0; 3];
+    assert_eq!(samples.fill(&mut out), 3);
+    assert_eq!(out, [0.25, 0.5, 0.75]);
+    assert_eq!(samples.fill(&mut out), 1);
+    assert_eq!(out, [1.0, 0.5, 0.75]);
+}
@@ -0,0 +1,59 @@
+extern crate envelope;
+
+use envelope::{BezierPoint, EnvelopeMut};
+use std::collections::VecDeque;
+
+type P = BezierPoint<f64, f64>;
+
+fn p(x: f64, y: f64) -> P {
+    BezierPoint::new(x, y, 0.0)
+}
+
+fn xs<'a, I: IntoIterator<Item=&'a P>>(points: I) -> Vec<(f64, f64)> {
+    points.into_iter().map(|p| (p.x, p.y)).collect()
+}
+
+/// Runs the same sequence of edits against any `EnvelopeMut`, returning the resulting indices.
+fn edit<E>(env: &mut E) -> Vec<usize>
+    where E: EnvelopeMut<X=f64, Y=f64, Point=P>,
+{
+    let indices = vec![
+        env.insert_point(p(5.0, 0.5)),
+        env.insert_point(p(0.0, 0.0)),
+        env.insert_point(p(10.0, 1.0)),
+        // Equal `x` points are placed after the existing ones.
+        env.insert_point(p(5.0, 0.75)),
+        env.move_point(0, 7.0, 0.25).unwrap(),
+        env.move_point(3, 5.0, 0.1).unwrap(),
+        // Moving a point without changing its `x` keeps it in place within the vertical jump.
+        env.move_point(0, 5.0, 0.9).unwrap(),
+    ];
+    assert_eq!(env.move_point(42, 0.0, 0.0), None);
+    indices
+}
+
+#[test]
+fn vec() {
+    let mut points: Vec<P> = vec![];
+    let indices = edit(&mut points);
+    assert_eq!(indices, vec![0, 0, 2, 2, 2, 2, 0]);
+    assert_eq!(xs(&points), vec![(5.0, 0.9), (5.0, 0.75), (5.0, 0.1), (7.0, 0.25)]);
+
+    assert_eq!(points.remove_point(1).map(|p| p.y), Some(0.75));
+    assert_eq!(points.remove_point(10), None);
+    EnvelopeMut::retain(&mut points, |p| p.y > 0.2);
+    assert_eq!(xs(&points), vec![(5.0, 0.9), (7.0, 0.25)]);
+}
+
+#[test]
+fn vec_deque() {
+    let mut points: VecDeque<P> = VecDeque::new();
+    let indices = edit(&mut points);
+    assert_eq!(indices, vec![0, 0, 2, 2, 2, 2, 0]);
+    assert_eq!(xs(&points), vec![(5.0, 0.9), (5.0, 0.75), (5.0, 0.1), (7.0, 0.25)]);
+
+    assert_eq!(points.remove_point(1).map(|p| p.y), Some(0.75));
+    assert_eq!(points.remove_point(10), None);
+    EnvelopeMut::retain(&mut points, |p| p.y > 0.2);
+    assert_eq!(xs(&points), vec![(5.0, 0.9), (7.0, 0.25)]);
+}
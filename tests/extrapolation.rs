@@ -0,0 +1,89 @@
+extern crate envelope;
+
+use envelope::{EasePoint, Envelope, Extrapolation, VecEnvelope};
+
+type Env = VecEnvelope<EasePoint<f64, f64>>;
+
+/// A ramp from 0 to 1 between x = 1 and x = 3.
+fn ramp() -> Env {
+    vec![EasePoint::new(1.0, 0.0, None), EasePoint::new(3.0, 1.0, None)].into_iter().collect()
+}
+
+#[test]
+fn none() {
+    let env = ramp();
+    assert_eq!(env.y(0.5), None);
+    assert_eq!(env.y(3.5), None);
+    assert_eq!(env.y_extrapolated(0.5, Extrapolation::None), None);
+    assert_eq!(env.y_extrapolated(2.0, Extrapolation::None), Some(0.5));
+    assert!(env.steps(0.5, 0.5).is_none());
+    assert_eq!(env.steps(2.0, 0.5).unwrap().collect::<Vec<_>>(), vec![0.5, 0.75, 1.0]);
+}
+
+#[test]
+fn hold() {
+    let env = ramp();
+    assert_eq!(env.y_extrapolated(-10.0, Extrapolation::Hold), Some(0.0));
+    assert_eq!(env.y_extrapolated(10.0, Extrapolation::Hold), Some(1.0));
+}
+
+#[test]
+fn linear() {
+    let env = ramp();
+    assert_eq!(env.y_extrapolated(0.0, Extrapolation::Linear), Some(-0.5));
+    assert_eq!(env.y_extrapolated(5.0, Extrapolation::Linear), Some(2.0));
+
+    let single: Env = vec![EasePoint::new(1.0, 0.25, None)].into_iter().collect();
+    assert_eq!(single.y_extrapolated(0.0, Extrapolation::Linear), Some(0.25));
+    assert_eq!(single.y_extrapolated(2.0, Extrapolation::Linear), Some(0.25));
+}
+
+#[test]
+fn looped() {
+    let env = ramp();
+    assert_eq!(env.y_extrapolated(4.0, Extrapolation::Loop), Some(0.5));
+    assert_eq!(env.y_extrapolated(5.0, Extrapolation::Loop), Some(0.0));
+    assert_eq!(env.y_extrapolated(7.5, Extrapolation::Loop), Some(0.25));
+    assert_eq!(env.y_extrapolated(0.5, Extrapolation::Loop), Some(0.75));
+    assert_eq!(env.y_extrapolated(-1.0, Extrapolation::Loop), Some(0.0));
+}
+
+#[test]
+fn ping_pong() {
+    let env = ramp();
+    assert_eq!(env.y_extrapolated(3.5, Extrapolation::PingPong), Some(0.75));
+    assert_eq!(env.y_extrapolated(5.0, Extrapolation::PingPong), Some(0.0));
+    assert_eq!(env.y_extrapolated(5.5, Extrapolation::PingPong), Some(0.25));
+    assert_eq!(env.y_extrapolated(0.5, Extrapolation::PingPong), Some(0.25));
+    assert_eq!(env.y_extrapolated(-2.0, Extrapolation::PingPong), Some(0.5));
+}
+
+#[test]
+fn integer_x() {
+    let env: VecEnvelope<EasePoint<u32, f64>> = vec![
+        EasePoint::new(2, 0.0, None),
+        EasePoint::new(6, 1.0, None),
+    ].into_iter().collect();
+    assert_eq!(env.y_extrapolated(0, Extrapolation::Loop), Some(0.5));
+    assert_eq!(env.y_extrapolated(9, Extrapolation::Loop), Some(0.75));
+    assert_eq!(env.y_extrapolated(1, Extrapolation::PingPong), Some(0.25));
+    assert_eq!(env.y_extrapolated(0, Extrapolation::Linear), Some(-0.5));
+}
+
+#[test]
+fn extrapolated_steps_and_cursor() {
+    let env = ramp().extrapolate(Extrapolation::Loop);
+    assert_eq!(env.y(4.0), Some(0.5));
+
+    // Steps may now begin before the first point and run forever past the last.
+    let steps: Vec<f64> = env.steps(0.0, 0.5).unwrap().take(12).collect();
+    assert_eq!(steps, vec![0.5, 0.75, 0.0, 0.25, 0.5, 0.75, 1.0, 0.25, 0.5, 0.75, 0.0, 0.25]);
+
+    let mut cursor = env.cursor();
+    assert_eq!(cursor.y_at(0.0), Some(0.5));
+    assert_eq!(cursor.y_at(2.0), Some(0.5));
+    assert_eq!(cursor.y_at(4.5), Some(0.75));
+
+    let held = ramp().extrapolate(Extrapolation::Hold);
+    assert_eq!(held.steps(2.0, 1.0).unwrap().take(4).collect::<Vec<_>>(), vec![0.5, 1.0, 1.0, 1.0]);
+}
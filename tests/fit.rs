@@ -0,0 +1,104 @@
+extern crate envelope;
+extern crate interpolation;
+
+use envelope::{AnyPoint, BezierPoint, CubicBezierPoint, EasePoint, Envelope, Fit, FitPoint,
+               HermitePoint, Point, SegmentShape, SplinePoint, VecEnvelope};
+use interpolation::EaseFunction;
+
+/// A recorded fader gesture with one sample per tick.
+fn gesture(n: usize) -> Vec<(f64, f64)> {
+    (0..n).map(|i| {
+        let x = i as f64 / 100.0;
+        (x, (x * 1.3).sin() * 0.5 + 0.5 + (x * 0.4).cos() * 0.2)
+    }).collect()
+}
+
+/// The samples of the given envelope at every tick between its first and last points.
+fn sample<'a, E>(env: &'a E, n: usize) -> Vec<(f64, f64)>
+    where E: Envelope<'a, X=f64, Y=f64>,
+{
+    (0..n + 1).map(|i| i as f64 / 100.0).map(|x| (x, env.y(x).unwrap())).collect()
+}
+
+/// Fit the points to the samples and check that the resulting envelope reproduces them.
+fn fit_within<P>(samples: &[(f64, f64)], tolerance: f64) -> Vec<P>
+    where P: FitPoint<X=f64, Y=f64>,
+{
+    let fit = Fit::<P>::new(samples, tolerance);
+    assert!(fit.max_error <= tolerance, "max_error {}", fit.max_error);
+    let env: VecEnvelope<P> = fit.points.into();
+    for &(x, y) in samples {
+        assert!((env.y(x).unwrap() - y).abs() <= fit.max_error + 1e-12, "at {}", x);
+    }
+    env.as_slice().to_vec()
+}
+
+#[test]
+fn recovers_ease_functions() {
+    for &ease_fn in &[EaseFunction::SineInOut, EaseFunction::CubicIn, EaseFunction::BounceOut] {
+        let env: VecEnvelope<EasePoint<f64, f64>> = vec![
+            EasePoint::new(0.0, 0.0, Some(ease_fn)),
+            EasePoint::new(2.0, 0.5, None),
+        ].into_iter().collect();
+        let fitted = fit_within::<EasePoint<f64, f64>>(&sample(&env, 200), 1e-9);
+        assert_eq!(fitted.len(), 2);
+        assert!(fitted[0].maybe_ease_fn == Some(ease_fn));
+    }
+}
+
+#[test]
+fn recovers_bezier_curves() {
+    let env: VecEnvelope<BezierPoint<f64, f64>> = vec![
+        BezierPoint::new(0.0, 0.0, 0.5),
+        BezierPoint::new(1.0, 1.0, -0.75),
+        BezierPoint::new(3.0, 0.0, 0.0),
+    ].into_iter().collect();
+    let fitted = fit_within::<BezierPoint<f64, f64>>(&sample(&env, 300), 1e-9);
+    assert_eq!(fitted.len(), 3);
+    assert!((fitted[0].curve - 0.5).abs() < 1e-9);
+    assert!((fitted[1].curve + 0.75).abs() < 1e-9);
+
+    let any = fit_within::<AnyPoint<f64, f64>>(&sample(&env, 300), 1e-9);
+    assert_eq!(any.len(), 3);
+    match any[0].shape {
+        SegmentShape::Bezier(curve) => assert!((curve - 0.5).abs() < 1e-9),
+        _ => panic!("expected a bezier segment"),
+    }
+}
+
+#[test]
+fn recorded_gesture() {
+    let samples = gesture(2_000);
+    let tolerance = 0.001;
+    let linear = fit_within::<EasePoint<f64, f64>>(&samples, tolerance).len();
+    let bezier = fit_within::<BezierPoint<f64, f64>>(&samples, tolerance).len();
+    let any = fit_within::<AnyPoint<f64, f64>>(&samples, tolerance).len();
+    let spline = fit_within::<SplinePoint<f64, f64>>(&samples, tolerance).len();
+    let hermite = fit_within::<HermitePoint<f64, f64>>(&samples, tolerance).len();
+    let cubic = fit_within::<CubicBezierPoint<f64, f64>>(&samples, tolerance).len();
+    for &len in &[linear, spline] {
+        assert!(len < 200, "{} points", len);
+    }
+    // Curves fitted by their shape need far fewer points than lines.
+    for &len in &[bezier, any, hermite, cubic] {
+        assert!(len < 100, "{} points", len);
+    }
+}
+
+#[test]
+fn degenerate_samples() {
+    let empty = Fit::<EasePoint<f64, f64>>::new(&[], 0.1);
+    assert!(empty.points.is_empty());
+    assert_eq!(empty.max_error, 0.0);
+
+    let single = Fit::<HermitePoint<f64, f64>>::new(&[(1.0, 0.5)], 0.1);
+    assert_eq!(single.points, vec![HermitePoint::new(1.0, 0.5, 0.0)]);
+
+    // Every sample is kept if need be.
+    let samples = vec![(0.0, 0.0), (1.0, 1.0), (2.0, -1.0), (3.0, 1.0)];
+    let fit = Fit::<EasePoint<f64, f64>>::new(&samples, 0.0);
+    assert_eq!(fit.points.len(), 4);
+    assert_eq!(fit.max_error, 0.0);
+    let ys: Vec<f64> = fit.points.iter().map(Point::y).collect();
+    assert_eq!(ys, vec![0.0, 1.0, -1.0, 1.0]);
+}
@@ -50,7 +50,7 @@ macro_rules! test_x_y_float {
             let points_a = Points(Vec::from_iter((0..1_000).map(|i| {
                 let x = i as $X;
                 let value = sine(x);
-                Point { x: x, y: value } 
+                Point { x, y: value }
             })));
             for i in 0..1_000 {
                 let x = i as $X;
@@ -145,7 +145,7 @@ macro_rules! test_x_y_int {
             let points_a = Points(Vec::from_iter((0..1_000).map(|i| {
                 let x = i as $X;
                 let value = sine(x);
-                Point { x: x, y: value } 
+                Point { x, y: value }
             })));
             for i in 0..1_000 {
                 let x = i as $X;
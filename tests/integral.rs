@@ -0,0 +1,79 @@
+extern crate envelope;
+
+mod common;
+
+use common::sampled_integral;
+use envelope::{AnyPoint, EasePoint, Envelope, HermitePoint, SegmentShape, VecEnvelope};
+use envelope::interpolate::Jump;
+use envelope::interpolation::EaseFunction;
+
+#[test]
+fn linear_segments() {
+    let env: VecEnvelope<EasePoint<f64, f64>> =
+        vec![(0.0, 0.0), (2.0, 2.0), (4.0, 0.0), (4.0, 1.0), (5.0, 1.0)]
+            .into_iter()
+            .map(|(x, y)| EasePoint::new(x, y, None))
+            .collect();
+    assert_eq!(env.integrate(0.0, 4.0), Some(4.0));
+    assert_eq!(env.integrate(1.0, 3.0), Some(3.0));
+    assert_eq!(env.integrate(3.0, 1.0), Some(-3.0));
+    assert_eq!(env.integrate(2.0, 2.0), Some(0.0));
+    assert_eq!(env.integrate(3.0, 5.0), Some(1.5));
+    assert_eq!(env.integrate(-1.0, 1.0), None);
+    assert_eq!(env.integrate(4.0, 6.0), None);
+}
+
+#[test]
+fn every_shape_matches_its_integral() {
+    let env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+        AnyPoint::new(0.0, 0.0, SegmentShape::Step(Jump::At(0.5))),
+        AnyPoint::new(1.0, 1.0, SegmentShape::Linear),
+        AnyPoint::new(2.0, -0.5, SegmentShape::Bezier(0.75)),
+        AnyPoint::new(3.0, 1.0, SegmentShape::Exponential(3.0)),
+        AnyPoint::new(4.0, 0.0, SegmentShape::Exponential(-2.0)),
+        AnyPoint::new(5.0, 1.0, SegmentShape::Cosine),
+        AnyPoint::new(6.0, 0.25, SegmentShape::Ease(EaseFunction::QuadraticInOut)),
+        AnyPoint::new(7.0, 1.0, SegmentShape::Ease(EaseFunction::BounceOut)),
+        AnyPoint::new(8.0, 0.0, SegmentShape::Linear),
+    ].into();
+    for i in 0..32 {
+        let x0 = i as f64 / 4.0;
+        for &x1 in &[x0, x0 + 0.25, x0 + 0.75, 8.0] {
+            if x1 > 8.0 {
+                continue;
+            }
+            let area = env.integrate(x0, x1).unwrap();
+            let expected = sampled_integral(|x| env.y(x).unwrap(), x0, x1);
+            assert!((area - expected).abs() < 1e-6, "{}..{}: {} != {}", x0, x1, area, expected);
+        }
+    }
+}
+
+#[test]
+fn unknown_shapes_are_integrated_numerically() {
+    let env: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 0.0, 2.0),
+        HermitePoint::new(1.0, 1.0, -1.0),
+        HermitePoint::new(3.0, 0.5, 0.0),
+    ].into_iter().collect();
+    for &(x0, x1) in &[(0.0, 1.0), (0.25, 2.5), (0.5, 3.0)] {
+        let expected = sampled_integral(|x| env.y(x).unwrap(), x0, x1);
+        assert!((env.integrate(x0, x1).unwrap() - expected).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn running_integral() {
+    let env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+        AnyPoint::new(0.0, 1.0, SegmentShape::Linear),
+        AnyPoint::new(1.0, 3.0, SegmentShape::Cosine),
+        AnyPoint::new(2.0, 2.0, SegmentShape::Linear),
+    ].into();
+    let areas: Vec<f64> = env.running_integral(0.5, 0.25).unwrap().collect();
+    assert_eq!(areas.len(), 7);
+    for (i, area) in areas.into_iter().enumerate() {
+        let x = 0.5 + 0.25 * i as f64;
+        assert!((area - env.integrate(0.5, x).unwrap()).abs() < 1e-12, "x = {}", x);
+    }
+    assert!(env.running_integral(-0.5, 0.25).is_none());
+}
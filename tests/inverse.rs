@@ -0,0 +1,130 @@
+extern crate envelope;
+
+use envelope::{BezierPoint, EasePoint, Envelope, HermitePoint, InverseError, VecEnvelope};
+use envelope::interpolation::EaseFunction;
+
+fn linear(points: &[(f64, f64)]) -> VecEnvelope<EasePoint<f64, f64>> {
+    points.iter().map(|&(x, y)| EasePoint::new(x, y, None)).collect()
+}
+
+#[test]
+fn linear_segments() {
+    let taper = linear(&[(0.0, 0.0), (0.5, 0.25), (1.0, 1.0)]);
+    assert_eq!(taper.x_at_y(0.125), Ok(0.25));
+    assert_eq!(taper.x_at_y(0.625), Ok(0.75));
+    assert_eq!(taper.x_at_y(1.0), Ok(1.0));
+    assert_eq!(taper.x_at_y(1.5), Err(InverseError::OutOfRange));
+
+    // Falling envelopes, flat runs and jumps.
+    let falling = linear(&[(0.0, 1.0), (1.0, 0.5), (2.0, 0.5), (2.0, 0.25), (3.0, 0.0)]);
+    assert_eq!(falling.x_at_y(0.75), Ok(0.5));
+    assert_eq!(falling.x_at_y(0.5), Ok(1.0));
+    assert_eq!(falling.x_at_y(0.375), Ok(2.0));
+    assert_eq!(falling.x_at_y(0.125), Ok(2.5));
+
+    assert_eq!(linear(&[]).x_at_y(0.0), Err(InverseError::Empty));
+    assert_eq!(linear(&[(1.0, 0.5)]).x_at_y(0.5), Ok(1.0));
+}
+
+#[test]
+fn curved_segments() {
+    let bezier: VecEnvelope<BezierPoint<f64, f64>> = vec![
+        BezierPoint::new(0.0, 0.0, 0.75),
+        BezierPoint::new(1.0, 0.5, -0.5),
+        BezierPoint::new(2.0, 1.0, 0.0),
+    ].into_iter().collect();
+    let eased: VecEnvelope<EasePoint<f64, f64>> = vec![
+        EasePoint::new(0.0, 0.0, Some(EaseFunction::QuadraticIn)),
+        EasePoint::new(1.0, 0.5, Some(EaseFunction::ExponentialOut)),
+        EasePoint::new(2.0, 1.0, None),
+    ].into_iter().collect();
+    for i in 0..21 {
+        let y = i as f64 / 20.0;
+        let x = bezier.x_at_y(y).unwrap();
+        assert!((bezier.y(x).unwrap() - y).abs() < 1e-9, "y = {}", y);
+        let x = eased.x_at_y(y).unwrap();
+        assert!((eased.y(x).unwrap() - y).abs() < 1e-9, "y = {}", y);
+    }
+}
+
+#[test]
+fn not_monotonic() {
+    let peak = linear(&[(0.0, 0.0), (1.0, 1.0), (2.0, 0.5)]);
+    assert_eq!(peak.x_at_y(0.25), Err(InverseError::NotMonotonic { idx: 1 }));
+
+    // The ends of the segment are in order but the curve overshoots them.
+    let overshoot: VecEnvelope<EasePoint<f64, f64>> = vec![
+        EasePoint::new(0.0, 0.0, Some(EaseFunction::BackOut)),
+        EasePoint::new(1.0, 1.0, None),
+    ].into_iter().collect();
+    assert_eq!(overshoot.x_at_y(0.5), Err(InverseError::NotMonotonic { idx: 0 }));
+
+    let overshoot: VecEnvelope<BezierPoint<f64, f64>> = vec![
+        BezierPoint::new(0.0, 0.0, 2.0),
+        BezierPoint::new(1.0, 1.0, 0.0),
+    ].into_iter().collect();
+    assert_eq!(overshoot.x_at_y(0.5), Err(InverseError::NotMonotonic { idx: 0 }));
+
+    // The curve stays within its ends but turns back on itself, crossing `0.76` three times.
+    let bounce: VecEnvelope<EasePoint<f64, f64>> = vec![
+        EasePoint::new(0.0, 0.0, Some(EaseFunction::BounceOut)),
+        EasePoint::new(1.0, 1.0, None),
+    ].into_iter().collect();
+    assert_eq!(bounce.xs_at_y(0.76).count(), 3);
+    assert_eq!(bounce.x_at_y(0.76), Err(InverseError::NotMonotonic { idx: 0 }));
+
+    // `SineOut` rises to `1` at a quarter of the way along, then falls back through `0`.
+    let sine: VecEnvelope<EasePoint<f64, f64>> = vec![
+        EasePoint::new(0.0, 0.0, Some(EaseFunction::SineOut)),
+        EasePoint::new(1.0, 1.0, None),
+    ].into_iter().collect();
+    assert_eq!(sine.x_at_y(0.5), Err(InverseError::NotMonotonic { idx: 0 }));
+}
+
+#[test]
+fn unknown_shapes() {
+    let rising: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 0.0, 0.5),
+        HermitePoint::new(1.0, 1.0, 0.5),
+    ].into_iter().collect();
+    for i in 0..21 {
+        let y = i as f64 / 20.0;
+        let x = rising.x_at_y(y).unwrap();
+        assert!((rising.y(x).unwrap() - y).abs() < 1e-12, "y = {}", y);
+    }
+
+    // Both ends lie within the range of the envelope, but the steep tangents overshoot them.
+    let overshoot: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 0.0, 4.0),
+        HermitePoint::new(1.0, 1.0, 4.0),
+    ].into_iter().collect();
+    assert_eq!(overshoot.x_at_y(0.5), Err(InverseError::NotMonotonic { idx: 0 }));
+
+    // A bump between equal `y`s.
+    let bump: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 0.0, 4.0),
+        HermitePoint::new(1.0, 0.0, -4.0),
+        HermitePoint::new(2.0, 1.0, 0.0),
+    ].into_iter().collect();
+    assert_eq!(bump.x_at_y(0.5), Err(InverseError::NotMonotonic { idx: 0 }));
+}
+
+#[test]
+fn prepared_inverse_matches_x_at_y() {
+    let env: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 1.0, -0.5),
+        HermitePoint::new(1.0, 0.5, 0.0),
+        HermitePoint::new(2.0, 0.5, 0.0),
+        HermitePoint::new(3.0, 0.0, -0.5),
+    ].into_iter().collect();
+    let inverse = env.inverse().unwrap();
+    for i in 0..21 {
+        let y = i as f64 / 20.0;
+        assert_eq!(inverse.x_at_y(y), env.x_at_y(y), "y = {}", y);
+    }
+    assert_eq!(inverse.x_at_y(1.5), Err(InverseError::OutOfRange));
+
+    assert!(linear(&[]).inverse().is_err());
+    let bump = linear(&[(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)]);
+    assert_eq!(bump.inverse().err(), Some(InverseError::NotMonotonic { idx: 1 }));
+}
@@ -0,0 +1,98 @@
+extern crate envelope;
+
+use envelope::{EasePoint, Loop, LoopMode, LoopPlayer, LoopingEnvelope, VecEnvelope};
+
+type Env = VecEnvelope<EasePoint<f64, f64>>;
+
+fn env() -> Env {
+    [(0.0, 0.0), (1.0, 1.0), (2.0, 0.0), (3.0, 1.0), (4.0, 0.0), (5.0, 0.5)].iter()
+        .map(|&(x, y)| EasePoint::new(x, y, None))
+        .collect()
+}
+
+/// Step the player `n` times, returning each position along with its value.
+fn play<'a>(player: &mut LoopPlayer<'a, Env>, n: usize) -> Vec<(f64, f64)> {
+    (0..n).filter_map(|_| { let x = player.x(); player.next().map(|y| (x, y)) }).collect()
+}
+
+fn xs(steps: &[(f64, f64)]) -> Vec<f64> {
+    steps.iter().map(|&(x, _)| x).collect()
+}
+
+#[test]
+fn forward_loop_and_release() {
+    let mut looping = LoopingEnvelope::new(env());
+    looping.maybe_loop = Some(Loop { start: 1.0, end: 3.0, mode: LoopMode::Forward,
+                                 maybe_crossfade: None });
+    looping.maybe_release = Some(4.0);
+
+    let mut player = looping.play(0.0, 0.5);
+    let steps = play(&mut player, 12);
+    assert_eq!(xs(&steps), vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 1.5, 2.0, 2.5, 3.0, 1.5]);
+    // The loop's ends share the same `y`, so the wrap is seamless.
+    let ys: Vec<f64> = steps.iter().map(|&(_, y)| y).collect();
+    assert_eq!(&ys[5..9], &[0.5, 1.0, 0.5, 0.0]);
+
+    player.gate_off();
+    assert!(!player.is_gate_on());
+    let steps = play(&mut player, 10);
+    assert_eq!(xs(&steps), vec![4.0, 4.5, 5.0]);
+}
+
+#[test]
+fn ping_pong_loop() {
+    let mut looping = LoopingEnvelope::new(env());
+    looping.maybe_loop = Some(Loop { start: 1.0, end: 3.0, mode: LoopMode::PingPong,
+                                 maybe_crossfade: None });
+
+    let mut player = looping.play(0.0, 0.75);
+    let steps = play(&mut player, 11);
+    assert_eq!(xs(&steps), vec![0.0, 0.75, 1.5, 2.25, 3.0, 2.25, 1.5, 1.25, 2.0, 2.75, 2.5]);
+
+    // Without a release point, closing the gate plays on from the current position.
+    player.gate_off();
+    assert_eq!(xs(&play(&mut player, 4)), vec![1.75, 2.5, 3.25, 4.0]);
+}
+
+#[test]
+fn sustain_point() {
+    let mut looping = LoopingEnvelope::new(env());
+    looping.maybe_sustain = Some(2.0);
+
+    let mut player = looping.play(0.0, 0.75);
+    assert_eq!(xs(&play(&mut player, 6)), vec![0.0, 0.75, 1.5, 2.0, 2.0, 2.0]);
+    player.gate_off();
+    assert_eq!(xs(&play(&mut player, 3)), vec![2.0, 2.75, 3.5]);
+
+    // Re-opening the gate after the sustain point has passed has no effect on playback.
+    player.gate_on();
+    assert_eq!(xs(&play(&mut player, 2)), vec![4.25, 5.0]);
+    assert_eq!(player.next(), None);
+}
+
+#[test]
+fn forward_loop_crossfade() {
+    // The loop's ends have different `y`s, so the wrap would jump from `0.0` back up to `1.0`.
+    let mut looping = LoopingEnvelope::new(env());
+    looping.maybe_loop = Some(Loop { start: 1.0, end: 2.0, mode: LoopMode::Forward,
+                                 maybe_crossfade: Some(0.25) });
+
+    let mut player = looping.play(0.0, 1.0 / 64.0);
+    let steps = play(&mut player, 400);
+    assert_eq!(steps.len(), 400);
+    for pair in steps.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        assert!((y1 - y0).abs() < 0.1, "{} -> {}: {} -> {}", x0, x1, y0, y1);
+    }
+
+    // Before the crossfade the loop plays as usual, and by the end it reaches the loop's start.
+    let at = |x: f64| steps.iter().rev().find(|&&(step_x, _)| step_x == x).unwrap().1;
+    assert_eq!(at(1.5), 0.5);
+    assert_eq!(at(1.875), 0.5);
+    assert_eq!(at(2.0), 1.0);
+
+    // Without a crossfade, the wrap jumps.
+    looping.maybe_loop.as_mut().unwrap().maybe_crossfade = None;
+    let steps = play(&mut looping.play(0.0, 1.0 / 64.0), 400);
+    assert!(steps.windows(2).any(|pair| (pair[1].1 - pair[0].1).abs() > 0.9));
+}
@@ -0,0 +1,74 @@
+extern crate envelope;
+
+use envelope::{EasePoint, Player, Retrigger, VecEnvelope};
+
+fn attack_decay() -> VecEnvelope<EasePoint<f64, f64>> {
+    vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.5)]
+        .into_iter()
+        .map(|(x, y)| EasePoint::new(x, y, None))
+        .collect()
+}
+
+#[test]
+fn reset_and_legato() {
+    let env = attack_decay();
+    let mut player = Player::new(&env, 0.0, 0.25, Retrigger::Reset);
+    assert_eq!(player.next(), None);
+
+    player.trigger();
+    assert_eq!(player.by_ref().take(3).collect::<Vec<_>>(), vec![0.0, 0.25, 0.5]);
+    player.trigger();
+    assert_eq!(player.by_ref().take(2).collect::<Vec<_>>(), vec![0.0, 0.25]);
+
+    player.set_retrigger(Retrigger::Legato);
+    player.trigger();
+    assert_eq!(player.by_ref().take(2).collect::<Vec<_>>(), vec![0.5, 0.75]);
+
+    // Once finished, a legato trigger starts from the beginning.
+    assert_eq!(player.by_ref().collect::<Vec<_>>(), vec![1.0, 0.875, 0.75, 0.625, 0.5]);
+    assert!(!player.is_playing());
+    player.trigger();
+    assert_eq!(player.next(), Some(0.0));
+}
+
+#[test]
+fn from_current_remaps_the_first_segment() {
+    let env = attack_decay();
+    let mut player = Player::new(&env, 0.0, 0.25, Retrigger::FromCurrent);
+    player.trigger();
+    assert_eq!(player.by_ref().take(7).last(), Some(0.75));
+    assert_eq!(player.level(), Some(0.75));
+
+    // The attack now runs from 0.75 up to 1.0 while the decay is untouched.
+    player.trigger();
+    let ys: Vec<f64> = player.by_ref().collect();
+    assert_eq!(ys, vec![0.75, 0.8125, 0.875, 0.9375, 1.0, 0.875, 0.75, 0.625, 0.5]);
+
+    // Triggering while stopped plays the envelope as is.
+    player.trigger();
+    assert_eq!(player.by_ref().take(2).collect::<Vec<_>>(), vec![0.0, 0.25]);
+}
+
+#[test]
+fn from_current_remaps_the_segment_containing_start() {
+    // Starting part way through the attack.
+    let env = attack_decay();
+    let mut player = Player::new(&env, 0.5, 0.25, Retrigger::FromCurrent);
+    player.trigger();
+    assert_eq!(player.by_ref().take(2).collect::<Vec<_>>(), vec![0.5, 0.75]);
+    player.trigger();
+    let ys: Vec<f64> = player.by_ref().collect();
+    assert_eq!(ys, vec![0.75, 0.875, 1.0, 0.875, 0.75, 0.625, 0.5]);
+
+    // Starting part way through the decay, the remap runs from the level down to its end.
+    let mut player = Player::new(&env, 1.25, 0.25, Retrigger::FromCurrent);
+    player.trigger();
+    assert_eq!(player.by_ref().take(3).collect::<Vec<_>>(), vec![0.875, 0.75, 0.625]);
+    player.trigger();
+    let ys: Vec<f64> = player.by_ref().collect();
+    let expected = [0.625, 0.625 - 0.125 / 3.0, 0.625 - 0.25 / 3.0, 0.5];
+    assert_eq!(ys.len(), expected.len());
+    for (y, expected) in ys.iter().zip(expected.iter()) {
+        assert!((y - expected).abs() < 1e-12, "{} != {}", y, expected);
+    }
+}
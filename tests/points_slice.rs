@@ -0,0 +1,79 @@
+extern crate envelope;
+
+mod common;
+
+use common::{Linear, Sorted, P};
+use envelope::Envelope;
+
+fn points() -> Vec<P> {
+    // Includes a vertical jump (two points sharing an `x`) to check the tie-breaking.
+    vec![
+        P { x: 0.0, y: 0.0 },
+        P { x: 2.0, y: 1.0 },
+        P { x: 3.0, y: 0.5 },
+        P { x: 3.0, y: 0.25 },
+        P { x: 7.0, y: 1.0 },
+        P { x: 10.0, y: 0.0 },
+    ]
+}
+
+#[test]
+fn binary_search_matches_linear_scan() {
+    let linear = Linear(points());
+    let sorted = Sorted(points());
+    let mut x = -1.5;
+    while x <= 11.5 {
+        assert_eq!(linear.point_idx_before(x), sorted.point_idx_before(x), "x = {}", x);
+        assert_eq!(linear.point_idx_on_or_before(x), sorted.point_idx_on_or_before(x), "x = {}", x);
+        assert_eq!(linear.point_idx_after(x), sorted.point_idx_after(x), "x = {}", x);
+        assert_eq!(linear.point_idx_on_or_after(x), sorted.point_idx_on_or_after(x), "x = {}", x);
+        assert_eq!(linear.point_at_with_idx(x), sorted.point_at_with_idx(x), "x = {}", x);
+        assert_eq!(linear.surrounding_points(x), sorted.surrounding_points(x), "x = {}", x);
+        assert_eq!(linear.y(x), sorted.y(x), "x = {}", x);
+        let linear_steps: Option<Vec<f64>> = linear.steps(x, 0.25).map(|s| s.collect());
+        let sorted_steps: Option<Vec<f64>> = sorted.steps(x, 0.25).map(|s| s.collect());
+        assert_eq!(linear_steps, sorted_steps, "x = {}", x);
+        assert_eq!(linear.dy_dx(x), sorted.dy_dx(x), "x = {}", x);
+        assert_eq!(linear.y_range(x, x + 2.5), sorted.y_range(x, x + 2.5), "x = {}", x);
+        assert_eq!(linear.integrate(x, x + 2.5), sorted.integrate(x, x + 2.5), "x = {}", x);
+        let linear_areas: Option<Vec<f64>> = linear.running_integral(x, 0.5).map(|a| a.collect());
+        let sorted_areas: Option<Vec<f64>> = sorted.running_integral(x, 0.5).map(|a| a.collect());
+        assert_eq!(linear_areas, sorted_areas, "x = {}", x);
+        x += 0.25;
+    }
+}
+
+#[test]
+fn inverse_matches_linear_scan() {
+    let rising = vec![
+        P { x: 0.0, y: 0.0 },
+        P { x: 2.0, y: 0.5 },
+        P { x: 3.0, y: 0.5 },
+        P { x: 3.0, y: 0.75 },
+        P { x: 7.0, y: 1.0 },
+    ];
+    let linear = Linear(rising.clone());
+    let sorted = Sorted(rising);
+    let mut y = -0.125;
+    while y <= 1.125 {
+        assert_eq!(linear.x_at_y(y), sorted.x_at_y(y), "y = {}", y);
+        y += 0.0625;
+    }
+    assert_eq!(sorted.x_at_y(0.5), Ok(2.0));
+    assert_eq!(sorted.x_at_y(0.625), Ok(3.0));
+}
+
+#[test]
+fn empty_and_single_point() {
+    let empty = Sorted(vec![]);
+    assert_eq!(empty.y(0.0), None);
+    assert_eq!(empty.point_idx_on_or_before(0.0), None);
+    assert_eq!(empty.point_idx_on_or_after(0.0), None);
+    assert!(empty.steps(0.0, 1.0).is_none());
+
+    let single = Sorted(vec![P { x: 1.0, y: 0.5 }]);
+    assert_eq!(single.y(1.0), Some(0.5));
+    assert_eq!(single.y(2.0), None);
+    assert_eq!(single.point_at(1.0), Some(&single.0[0]));
+    assert_eq!(single.point_at(0.0), None);
+}
@@ -0,0 +1,94 @@
+extern crate envelope;
+
+mod common;
+
+use common::sampled_range;
+use envelope::{AnyPoint, Bounds, EasePoint, Envelope, HermitePoint, SegmentShape, VecEnvelope};
+use envelope::interpolate::Jump;
+use envelope::interpolation::EaseFunction;
+
+fn assert_contains_sampled<'a, E>(env: &'a E, x0: f64, x1: f64)
+    where E: Envelope<'a, X=f64, Y=f64>,
+          E::Point: envelope::Point<X=f64, Y=f64>,
+{
+    let (min, max) = env.y_range(x0, x1).unwrap();
+    let sampled = sampled_range(env, x0, x1);
+    assert!(min <= sampled.0 && sampled.0 - min < 1e-6, "{}..{}: {} {}", x0, x1, min, sampled.0);
+    assert!(max >= sampled.1 && max - sampled.1 < 1e-6, "{}..{}: {} {}", x0, x1, max, sampled.1);
+}
+
+#[test]
+fn linear_segments() {
+    let env: VecEnvelope<EasePoint<f64, f64>> =
+        vec![(0.0, 0.0), (2.0, 1.0), (2.0, 3.0), (3.0, -1.0)]
+            .into_iter()
+            .map(|(x, y)| EasePoint::new(x, y, None))
+            .collect();
+    assert_eq!(env.y_range(0.0, 1.0), Some((0.0, 0.5)));
+    assert_eq!(env.y_range(1.0, 0.0), Some((0.0, 0.5)));
+    // Both ends of the vertical segment are included.
+    assert_eq!(env.y_range(2.0, 2.0), Some((1.0, 3.0)));
+    assert_eq!(env.y_range(1.0, 2.5), Some((0.5, 3.0)));
+    assert_eq!(env.y_range(-1.0, 1.0), None);
+    assert_eq!(env.bounds(), Some(Bounds { x: (0.0, 3.0), y: (-1.0, 3.0) }));
+
+    let single: VecEnvelope<EasePoint<f64, f64>> = vec![EasePoint::new(1.0, 0.5, None)].into();
+    assert_eq!(single.bounds(), Some(Bounds { x: (1.0, 1.0), y: (0.5, 0.5) }));
+    assert_eq!(VecEnvelope::<EasePoint<f64, f64>>::default().bounds(), None);
+}
+
+#[test]
+fn extrema_within_segments() {
+    let env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+        AnyPoint::new(0.0, 0.0, SegmentShape::Bezier(2.0)),
+        AnyPoint::new(1.0, 1.0, SegmentShape::Ease(EaseFunction::BackOut)),
+        AnyPoint::new(2.0, 0.0, SegmentShape::Ease(EaseFunction::ElasticInOut)),
+        AnyPoint::new(3.0, 0.5, SegmentShape::Step(Jump::At(0.5))),
+        AnyPoint::new(4.0, 1.0, SegmentShape::Cosine),
+        AnyPoint::new(5.0, 0.0, SegmentShape::Linear),
+    ].into();
+    assert_eq!(env.y_range(0.0, 1.0), Some((0.0, 1.125)));
+    assert_eq!(env.y_range(0.0, 0.5), Some((0.0, 1.0)));
+    for &(x0, x1) in &[(0.0, 5.0), (1.0, 2.0), (1.25, 1.5), (2.0, 3.0), (2.1, 2.4), (3.0, 4.5)] {
+        assert_contains_sampled(&env, x0, x1);
+    }
+}
+
+#[test]
+fn unknown_shapes_are_solved_numerically() {
+    let env: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 0.0, 4.0),
+        HermitePoint::new(1.0, 1.0, -4.0),
+        HermitePoint::new(3.0, 0.5, 0.0),
+    ].into_iter().collect();
+    for &(x0, x1) in &[(0.0, 1.0), (0.25, 2.5), (0.0, 3.0)] {
+        assert_contains_sampled(&env, x0, x1);
+    }
+}
+
+#[test]
+fn range_index_matches_y_range() {
+    let shapes = [
+        SegmentShape::Linear,
+        SegmentShape::Bezier(1.5),
+        SegmentShape::Ease(EaseFunction::BackInOut),
+        SegmentShape::Exponential(2.0),
+        SegmentShape::Cosine,
+    ];
+    let env: VecEnvelope<AnyPoint<f64, f64>> = (0..200)
+        .map(|i| {
+            let x = (i / 3 * 2) as f64 + (i % 3) as f64 * 0.5;
+            let y = ((i * 37) % 11) as f64 / 10.0;
+            AnyPoint::new(x, y, shapes[i % shapes.len()])
+        })
+        .collect::<Vec<_>>()
+        .into();
+    let index = env.range_index();
+    let last = env.points().last().unwrap().x;
+    for i in 0..60 {
+        let x0 = (i * 7 % 131) as f64 * 0.95;
+        let x1 = (x0 + (i * 13 % 29) as f64 * 0.5).min(last);
+        assert_eq!(index.y_range(x0, x1), env.y_range(x0, x1), "{}..{}", x0, x1);
+    }
+    assert_eq!(index.y_range(0.0, last + 1.0), None);
+}
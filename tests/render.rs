@@ -0,0 +1,48 @@
+extern crate envelope;
+
+use envelope::{BezierPoint, EasePoint, Envelope, Extrapolation, StepPoint, VecEnvelope};
+use envelope::interpolate::Jump;
+
+#[test]
+fn render_matches_steps() {
+    let env: VecEnvelope<BezierPoint<f64, f64>> = vec![
+        BezierPoint::new(0.0, 0.0, 0.5),
+        BezierPoint::new(1.0, 1.0, 0.0),
+        BezierPoint::new(1.0, 0.25, -0.5),
+        BezierPoint::new(3.0, 0.75, 0.0),
+    ].into_iter().collect();
+    for &(start, step) in &[(0.0, 0.1), (0.5, 0.25), (1.0, 1.0 / 3.0), (3.0, 0.5)] {
+        let stepped: Vec<f64> = env.steps(start, step).unwrap().collect();
+        let mut out = vec![-1.0; stepped.len() + 4];
+        assert_eq!(env.render(start, step, &mut out), stepped.len(), "start = {}", start);
+        assert_eq!(&out[..stepped.len()], &stepped[..], "start = {}", start);
+        assert!(out[stepped.len()..].iter().all(|&y| y == -1.0));
+    }
+    assert_eq!(env.render(-1.0, 0.5, &mut [0.0; 4]), 0);
+}
+
+#[test]
+fn fill_continues_between_buffers() {
+    let env: VecEnvelope<StepPoint<f64, bool>> = vec![
+        StepPoint::new(0.0, false, Jump::At(0.5)),
+        StepPoint::new(1.0, true, Jump::End),
+        StepPoint::new(2.0, false, Jump::End),
+    ].into_iter().collect();
+    let mut steps = env.steps(0.0, 0.25).unwrap();
+    let mut out = [false; 3];
+    assert_eq!(steps.fill(&mut out), 3);
+    assert_eq!(out, [false, false, true]);
+    assert_eq!(steps.fill(&mut out), 3);
+    assert_eq!(out, [true, true, true]);
+    assert_eq!(steps.fill(&mut out), 3);
+    assert_eq!(out, [true, true, false]);
+    assert_eq!(steps.fill(&mut out), 0);
+
+    let looped = vec![EasePoint::new(1.0, 0.0, None), EasePoint::new(3.0, 1.0, None)]
+        .into_iter()
+        .collect::<VecEnvelope<_>>()
+        .extrapolate(Extrapolation::Loop);
+    let mut out = [0.0; 12];
+    assert_eq!(looped.render(0.0, 0.5, &mut out), 12);
+    assert_eq!(out, [0.5, 0.75, 0.0, 0.25, 0.5, 0.75, 1.0, 0.25, 0.5, 0.75, 0.0, 0.25]);
+}
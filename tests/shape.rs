@@ -0,0 +1,47 @@
+extern crate envelope;
+
+use envelope::{Envelope, HermitePoint, Spline, SplinePoint, VecEnvelope};
+use envelope::interpolate::CatmullRom;
+
+/// Segments without a known `SegmentShape` are never assumed to be flat between equal `y`s.
+#[test]
+fn unknown_shapes_between_equal_ys_are_not_flat() {
+    // A hermite bump between two points at `0.0`, following `4x - 4x^2`.
+    let bump: VecEnvelope<HermitePoint<f64, f64>> =
+        vec![HermitePoint::new(0.0, 0.0, 4.0), HermitePoint::new(1.0, 0.0, -4.0)]
+            .into_iter()
+            .collect();
+
+    let xs: Vec<f64> = bump.xs_at_y(0.5).collect();
+    assert_eq!(xs.len(), 2);
+    assert!((xs[0] - (1.0 - 0.5f64.sqrt()) / 2.0).abs() < 1e-12);
+    assert!((xs[1] - (1.0 + 0.5f64.sqrt()) / 2.0).abs() < 1e-12);
+    assert_eq!(bump.xs_at_y(0.0).collect::<Vec<_>>(), vec![0.0, 1.0]);
+
+    assert!((bump.integrate(0.0, 1.0).unwrap() - 2.0 / 3.0).abs() < 1e-12);
+    assert!((bump.integrate(0.0, 0.5).unwrap() - 1.0 / 3.0).abs() < 1e-12);
+
+    for &x in &[0.0, 0.25, 0.5, 0.75] {
+        assert!((bump.dy_dx(x).unwrap() - (4.0 - 8.0 * x)).abs() < 1e-6, "x = {}", x);
+    }
+
+    let (min, max) = bump.y_range(0.0, 1.0).unwrap();
+    assert!(min.abs() < 1e-12 && (max - 1.0).abs() < 1e-9, "({}, {})", min, max);
+    assert!((bump.bounds().unwrap().y.1 - 1.0).abs() < 1e-9);
+    assert_eq!(bump.range_index().y_range(0.0, 1.0), bump.y_range(0.0, 1.0));
+
+    // A segment that is flat at `y` yields only its start.
+    let flat: VecEnvelope<HermitePoint<f64, f64>> =
+        vec![HermitePoint::new(0.0, 0.0, 0.0), HermitePoint::new(1.0, 0.0, 0.0)]
+            .into_iter()
+            .collect();
+    assert_eq!(flat.xs_at_y(0.0).collect::<Vec<_>>(), vec![0.0]);
+
+    // A Catmull-Rom segment between two points at `1.0` overshoots to `1.125`.
+    let spline: VecEnvelope<SplinePoint<f64, f64>> =
+        vec![(0.0, 0.0), (1.0, 1.0), (2.0, 1.0), (3.0, 0.0)].into_iter()
+            .map(|(x, y)| SplinePoint::new(x, y, Some(Spline::CatmullRom(CatmullRom::Uniform))))
+            .collect();
+    let (min, max) = spline.y_range(1.0, 2.0).unwrap();
+    assert!((min - 1.0).abs() < 1e-12 && (max - 1.125).abs() < 1e-9, "({}, {})", min, max);
+}
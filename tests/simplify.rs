@@ -0,0 +1,67 @@
+extern crate envelope;
+
+use envelope::{BezierPoint, EasePoint, Envelope, Spline, SplinePoint, VecEnvelope};
+
+/// The largest difference in `y` between the envelopes, found by densely sampling them.
+fn max_deviation<'a, E>(a: &'a E, b: &'a E, x0: f64, x1: f64) -> f64
+    where E: Envelope<'a, X=f64, Y=f64>,
+{
+    let n = 100_000;
+    (0..n + 1)
+        .map(|i| x0 + (x1 - x0) * i as f64 / n as f64)
+        .map(|x| (a.y(x).unwrap() - b.y(x).unwrap()).abs())
+        .fold(0.0, f64::max)
+}
+
+/// A recorded fader gesture with one point per tick.
+fn gesture(n: usize) -> Vec<(f64, f64)> {
+    (0..n).map(|i| {
+        let x = i as f64 / 100.0;
+        (x, (x * 1.3).sin() * 0.5 + 0.5 + (x * 7.0).sin() * 0.02)
+    }).collect()
+}
+
+#[test]
+fn collinear_points_are_removed() {
+    let env: VecEnvelope<EasePoint<f64, f64>> = (0..100)
+        .map(|i| EasePoint::new(i as f64, if i < 50 { 0.5 } else { 0.5 + (i - 50) as f64 / 4.0 }, None))
+        .collect();
+    let xs: Vec<f64> = env.simplify(1e-12).iter().map(|p| p.x).collect();
+    assert_eq!(xs, vec![0.0, 50.0, 99.0]);
+    assert_eq!(env.simplify(0.5).len(), 3);
+}
+
+#[test]
+fn recorded_gesture() {
+    let env: VecEnvelope<EasePoint<f64, f64>> =
+        gesture(2_000).into_iter().map(|(x, y)| EasePoint::new(x, y, None)).collect();
+    for &tolerance in &[0.001, 0.01, 0.05] {
+        let simplified: VecEnvelope<_> = env.simplify(tolerance).into();
+        assert!(simplified.as_slice().len() < 400, "{} points", simplified.as_slice().len());
+        assert!(max_deviation(&env, &simplified, 0.0, 19.99) <= tolerance + 1e-12);
+    }
+
+    // Jumps are retained.
+    let jump: VecEnvelope<EasePoint<f64, f64>> =
+        vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (2.0, 1.0)].into_iter()
+            .map(|(x, y)| EasePoint::new(x, y, None))
+            .collect();
+    assert_eq!(jump.simplify(0.1).len(), 4);
+}
+
+#[test]
+fn curved_segments() {
+    let bezier: VecEnvelope<BezierPoint<f64, f64>> =
+        gesture(500).into_iter().map(|(x, y)| BezierPoint::new(x, y, 0.25)).collect();
+    let simplified: VecEnvelope<_> = bezier.simplify(0.01).into();
+    assert!(simplified.as_slice().len() < 100, "{} points", simplified.as_slice().len());
+    assert!(max_deviation(&bezier, &simplified, 0.0, 4.99) <= 0.01 + 1e-12);
+
+    // Splines depend on their neighbours, which change as points are removed.
+    let spline: VecEnvelope<SplinePoint<f64, f64>> = gesture(500).into_iter()
+        .map(|(x, y)| SplinePoint::new(x, y, Some(Spline::Monotone)))
+        .collect();
+    let simplified: VecEnvelope<_> = spline.simplify(0.01).into();
+    assert!(simplified.as_slice().len() < 100, "{} points", simplified.as_slice().len());
+    assert!(max_deviation(&spline, &simplified, 0.0, 4.99) <= 0.01 + 1e-9);
+}
@@ -0,0 +1,118 @@
+extern crate envelope;
+
+use envelope::{Envelope, HermitePoint, Point, Spline, SplinePoint, VecEnvelope};
+use envelope::interpolate::CatmullRom;
+
+fn spline(catmull_rom: CatmullRom, points: &[(f64, f64)]) -> VecEnvelope<SplinePoint<f64, f64>> {
+    let spline = Some(Spline::CatmullRom(catmull_rom));
+    points.iter().map(|&(x, y)| SplinePoint::new(x, y, spline)).collect()
+}
+
+#[test]
+fn uniform_catmull_rom_matches_the_classic_formula() {
+    let ys = [0.0, 1.0, 0.25, 0.75];
+    let points: Vec<_> = ys.iter().enumerate().map(|(i, &y)| (i as f64, y)).collect();
+    let env = spline(CatmullRom::Uniform, &points);
+    let (p0, p1, p2, p3) = (ys[0], ys[1], ys[2], ys[3]);
+    for &t in &[0.1, 0.25, 0.5, 0.9] {
+        let expected = 0.5 * (2.0 * p1
+            + (p2 - p0) * t
+            + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t
+            + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t * t * t);
+        assert!((env.y(1.0 + t).unwrap() - expected).abs() < 1e-9, "t = {}", t);
+    }
+}
+
+#[test]
+fn catmull_rom_passes_through_every_point() {
+    let points = [(0.0, 0.0), (0.5, 1.0), (3.0, -1.0), (3.5, 0.5), (6.0, 0.0)];
+    for &catmull_rom in &[CatmullRom::Uniform, CatmullRom::Centripetal, CatmullRom::Chordal] {
+        let env = spline(catmull_rom, &points);
+        for &(x, y) in &points {
+            assert_eq!(env.y(x), Some(y));
+            // The curve is continuous either side of each point.
+            for &dx in &[-1e-9, 1e-9] {
+                if let Some(y_near) = env.y(x + dx) {
+                    assert!((y_near - y).abs() < 1e-6, "{:?} at x = {}", catmull_rom, x + dx);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn catmull_rom_uses_the_neighbouring_points() {
+    let env = spline(CatmullRom::Centripetal, &[(0.0, 0.0), (1.0, 1.0), (2.0, 1.0), (3.0, 0.0)]);
+    let points = env.as_slice();
+
+    // Without neighbours the middle segment is flat, but the neighbours make it bulge upwards.
+    assert_eq!(Point::interpolate(1.5, &points[1], &points[2]), 1.0);
+    let y = env.y(1.5).unwrap();
+    assert!(y > 1.0);
+
+    // Every method of evaluation sees the same neighbours.
+    assert_eq!(env.cursor().y_at(1.5), Some(y));
+    let stepped: Vec<f64> = env.steps(0.0, 0.5).unwrap().collect();
+    let expected: Vec<f64> = (0..7).map(|i| env.y(i as f64 * 0.5).unwrap()).collect();
+    assert_eq!(stepped, expected);
+    assert_eq!(stepped[3], y);
+
+    let xs: Vec<f64> = env.xs_at_y(0.5).collect();
+    assert_eq!(xs.len(), 2);
+    for x in xs {
+        assert!((env.y(x).unwrap() - 0.5).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn hermite_tangents() {
+    // Flat tangents produce a smoothstep.
+    let flat: VecEnvelope<HermitePoint<f64, f64>> =
+        vec![HermitePoint::new(0.0, 0.0, 0.0), HermitePoint::new(2.0, 1.0, 0.0)].into();
+    for &s in &[0.1, 0.5, 0.8] {
+        let expected = s * s * (3.0 - 2.0 * s);
+        assert!((flat.y(2.0 * s).unwrap() - expected).abs() < 1e-12);
+    }
+
+    // Tangents that match the slope between the points produce a straight line.
+    let line: VecEnvelope<HermitePoint<f64, f64>> =
+        vec![HermitePoint::new(0.0, 1.0, -0.5), HermitePoint::new(4.0, -1.0, -0.5)].into();
+    for &x in &[0.0, 1.0, 2.5, 4.0] {
+        assert!((line.y(x).unwrap() - (1.0 - x * 0.5)).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn monotone_never_overshoots() {
+    let points = [(0.0, 0.0), (1.0, 0.0), (1.5, 1.0), (4.0, 1.0), (4.5, 0.2), (5.0, 0.1),
+                  (8.0, 0.9)];
+    let env: VecEnvelope<SplinePoint<f64, f64>> = points.iter()
+        .map(|&(x, y)| SplinePoint::new(x, y, Some(Spline::Monotone)))
+        .collect();
+    for w in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+        let (lo, hi) = (y0.min(y1), y0.max(y1));
+        let mut last_y = y0;
+        for i in 0..=100 {
+            let y = env.y(x0 + (x1 - x0) * i as f64 / 100.0).unwrap();
+            assert!(lo <= y && y <= hi, "y = {} between {:?} and {:?}", y, w[0], w[1]);
+            // Each segment is monotonic.
+            assert!((y - last_y) * (y1 - y0) >= 0.0);
+            last_y = y;
+        }
+    }
+
+    // The same points overshoot with a Catmull-Rom spline.
+    let catmull_rom = spline(CatmullRom::Centripetal, &points);
+    assert!((0..100).any(|i| catmull_rom.y(1.5 + 2.5 * i as f64 / 100.0).unwrap() > 1.0));
+}
+
+#[test]
+fn monotone_follows_a_straight_line() {
+    let env: VecEnvelope<SplinePoint<f64, f64>> = [(0.0, 0.0), (1.0, 0.5), (3.0, 1.5)].iter()
+        .map(|&(x, y)| SplinePoint::new(x, y, Some(Spline::Monotone)))
+        .collect();
+    for &x in &[0.25, 0.5, 1.5, 2.75] {
+        assert!((env.y(x).unwrap() - x * 0.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,62 @@
+extern crate envelope;
+
+use envelope::{Envelope, EnvelopeMut, Extrapolation, SegmentShape, StepPoint, VecEnvelope};
+use envelope::interpolate::Jump;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Waveform {
+    Sine,
+    Saw,
+    Square,
+}
+
+#[test]
+fn holds_until_the_jump() {
+    let at = |jump| -> VecEnvelope<StepPoint<f64, i32>> {
+        vec![StepPoint::new(0.0, 1, jump), StepPoint::new(4.0, 2, jump)].into()
+    };
+    let xs = [0.0, 0.5, 1.0, 3.9, 4.0];
+    let ys = |env: VecEnvelope<_>| xs.iter().map(|&x| env.y(x).unwrap()).collect::<Vec<_>>();
+    assert_eq!(ys(at(Jump::End)), vec![1, 1, 1, 1, 2]);
+    assert_eq!(ys(at(Jump::Start)), vec![1, 2, 2, 2, 2]);
+    assert_eq!(ys(at(Jump::At(0.25))), vec![1, 1, 2, 2, 2]);
+    assert_eq!(ys(at(Jump::At(0.0))), ys(at(Jump::Start)));
+}
+
+#[test]
+fn values_need_not_be_spatial() {
+    let gate: VecEnvelope<StepPoint<f64, bool>> = vec![
+        StepPoint::new(0.0, false, Jump::End),
+        StepPoint::new(1.0, true, Jump::End),
+        StepPoint::new(3.0, false, Jump::End),
+    ].into();
+    let stepped: Vec<bool> = gate.steps(0.0, 0.5).unwrap().collect();
+    assert_eq!(stepped, vec![false, false, true, true, true, true, false]);
+    assert_eq!(gate.cursor().y_at(2.0), Some(true));
+    assert_eq!(gate.y_extrapolated(10.0, Extrapolation::Linear), Some(false));
+    assert_eq!(gate.y_extrapolated(4.5, Extrapolation::Loop), Some(true));
+
+    let mut waveforms = vec![
+        StepPoint::new(0.0, Waveform::Sine, Jump::End),
+        StepPoint::new(2.0, Waveform::Saw, Jump::End),
+    ];
+    waveforms.insert_point(StepPoint::new(1.0, Waveform::Square, Jump::End));
+    assert_eq!(waveforms.move_point(0, 3.0, Waveform::Sine), Some(2));
+    let env = VecEnvelope::from(waveforms);
+    assert_eq!(env.y(1.5), Some(Waveform::Square));
+    assert_eq!(env.y(2.5), Some(Waveform::Saw));
+    assert_eq!(env.y(3.0), Some(Waveform::Sine));
+}
+
+#[test]
+fn step_segment_shape() {
+    let shape = SegmentShape::Step(Jump::At(0.5));
+    assert_eq!(shape.y((0.0, 1.0), (2.0, 3.0), 0.5), 1.0);
+    assert_eq!(shape.y((0.0, 1.0), (2.0, 3.0), 1.5), 3.0);
+
+    let mut xs = vec![];
+    shape.xs_at_y((0.0, 1.0), (2.0, 3.0), 2.0, &mut xs);
+    shape.xs_at_y((0.0, 1.0), (2.0, 3.0), 1.0, &mut xs);
+    SegmentShape::Step(Jump::End).xs_at_y((0.0, 1.0), (2.0, 3.0), 3.0, &mut xs);
+    assert_eq!(xs, vec![1.0, 0.0, 2.0]);
+}
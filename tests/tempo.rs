@@ -0,0 +1,154 @@
+extern crate envelope;
+
+mod common;
+
+use common::sampled_integral;
+use envelope::{AnyPoint, EasePoint, HermitePoint, SegmentShape, TempoMap, VecEnvelope};
+use envelope::interpolate::Jump;
+use envelope::interpolation::EaseFunction;
+
+#[test]
+fn constant_and_linear_ramps() {
+    let env: VecEnvelope<EasePoint<f64, f64>> = vec![
+        EasePoint::new(0.0, 120.0, None),
+        EasePoint::new(4.0, 120.0, None),
+        EasePoint::new(8.0, 60.0, None),
+    ].into_iter().collect();
+    let tempo = TempoMap::new(&env).unwrap();
+    assert_eq!(tempo.seconds_at(2.0), 1.0);
+    assert_eq!(tempo.seconds_at(4.0), 2.0);
+    let ramp = 2.0 + 4.0 * 2f64.ln();
+    assert!((tempo.seconds_at(8.0) - ramp).abs() < 1e-12);
+    assert!((tempo.seconds_at(9.0) - (ramp + 1.0)).abs() < 1e-12);
+    assert_eq!(tempo.seconds_at(-2.0), -1.0);
+    assert_eq!(tempo.bpm(6.0), 90.0);
+
+    assert!((tempo.beat_at(ramp) - 8.0).abs() < 1e-12);
+    assert!((tempo.beat_at(1.5) - 3.0).abs() < 1e-12);
+    assert_eq!(tempo.sample_at(1.0, 48_000.0), 24_000.0);
+    assert_eq!(tempo.beat_at_sample(48_000, 48_000.0), 2.0);
+}
+
+#[test]
+fn every_shape_matches_its_integral() {
+    let env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+        AnyPoint::new(1.0, 100.0, SegmentShape::Linear),
+        AnyPoint::new(2.0, 140.0, SegmentShape::Step(Jump::At(0.25))),
+        AnyPoint::new(3.0, 90.0, SegmentShape::Exponential(2.0)),
+        AnyPoint::new(4.0, 180.0, SegmentShape::Exponential(-3.0)),
+        AnyPoint::new(5.0, 60.0, SegmentShape::Cosine),
+        AnyPoint::new(6.0, 150.0, SegmentShape::Bezier(0.75)),
+        AnyPoint::new(7.0, 80.0, SegmentShape::Bezier(-0.5)),
+        AnyPoint::new(8.0, 120.0, SegmentShape::Ease(EaseFunction::QuadraticInOut)),
+        AnyPoint::new(9.0, 70.0, SegmentShape::Ease(EaseFunction::BounceOut)),
+        AnyPoint::new(10.0, 130.0, SegmentShape::Linear),
+    ].into();
+    let tempo = TempoMap::new(&env).unwrap();
+    for i in 0..45 {
+        let beat = i as f64 / 4.0;
+        let expected = sampled_integral(|b| 60.0 / tempo.bpm(b), 0.0, beat);
+        let secs = tempo.seconds_at(beat);
+        assert!((secs - expected).abs() < 1e-6, "beat = {}: {} != {}", beat, secs, expected);
+        assert!((tempo.beat_at(secs) - beat).abs() < 1e-9, "beat = {}", beat);
+    }
+}
+
+#[test]
+fn eased_segments_are_solved_exactly() {
+    let eases = [
+        EaseFunction::QuadraticIn,
+        EaseFunction::QuadraticOut,
+        EaseFunction::QuadraticInOut,
+        EaseFunction::SineInOut,
+        EaseFunction::ExponentialIn,
+        EaseFunction::ExponentialOut,
+        EaseFunction::ExponentialInOut,
+    ];
+    for (n, &ease_fn) in eases.iter().enumerate() {
+        for &(t0, t1) in [(60.0, 180.0), (150.0, 90.0)].iter() {
+            let env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+                AnyPoint::new(0.0, t0, SegmentShape::Ease(ease_fn)),
+                AnyPoint::new(4.0, t1, SegmentShape::Linear),
+            ].into();
+            let tempo = TempoMap::new(&env).unwrap();
+            for i in 1..17 {
+                let beat = i as f64 / 4.0;
+                let expected = sampled_integral(|b| 60.0 / tempo.bpm(b), 0.0, beat);
+                let secs = tempo.seconds_at(beat);
+                assert!((secs - expected).abs() < 1e-6, "ease {}: {} != {}", n, secs, expected);
+                assert!((tempo.beat_at(secs) - beat).abs() < 1e-12, "ease {}: {}", n, beat);
+            }
+        }
+    }
+}
+
+#[test]
+fn unknown_shapes_are_integrated_numerically() {
+    let env: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 90.0, 0.0),
+        HermitePoint::new(2.0, 150.0, 40.0),
+        HermitePoint::new(4.0, 100.0, 0.0),
+    ].into_iter().collect();
+    let tempo = TempoMap::new(&env).unwrap();
+    for i in 1..9 {
+        let beat = i as f64 / 2.0;
+        let expected = sampled_integral(|b| 60.0 / tempo.bpm(b), 0.0, beat);
+        assert!((tempo.seconds_at(beat) - expected).abs() < 1e-6, "beat = {}", beat);
+    }
+
+    // Equal tempos at either end do not make a bump between them constant.
+    let env: VecEnvelope<HermitePoint<f64, f64>> = vec![
+        HermitePoint::new(0.0, 120.0, 60.0),
+        HermitePoint::new(2.0, 120.0, -60.0),
+    ].into_iter().collect();
+    let tempo = TempoMap::new(&env).unwrap();
+    let expected = sampled_integral(|b| 60.0 / tempo.bpm(b), 0.0, 2.0);
+    assert!(expected < 0.9);
+    assert!((tempo.seconds_at(2.0) - expected).abs() < 1e-6);
+    assert!((tempo.beat_at(expected) - 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn steps_in_seconds() {
+    // Two beats at 60 BPM followed by a jump to 120 BPM.
+    let tempo_env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+        AnyPoint::new(0.0, 60.0, SegmentShape::Step(Jump::End)),
+        AnyPoint::new(2.0, 120.0, SegmentShape::Linear),
+    ].into();
+    let tempo = TempoMap::new(&tempo_env).unwrap();
+    let beats: VecEnvelope<EasePoint<f64, f64>> =
+        vec![EasePoint::new(0.0, 0.0, None), EasePoint::new(4.0, 1.0, None)].into_iter().collect();
+    let ys: Vec<f64> = tempo.steps(&beats, 0.0, 0.5).collect();
+    assert_eq!(ys, vec![0.0, 0.125, 0.25, 0.375, 0.5, 0.75, 1.0]);
+
+    // Stepping forwards agrees with solving each step from scratch, whatever the segment's shape.
+    let tempo_env: VecEnvelope<AnyPoint<f64, f64>> = vec![
+        AnyPoint::new(0.0, 90.0, SegmentShape::Ease(EaseFunction::BounceOut)),
+        AnyPoint::new(2.0, 150.0, SegmentShape::Cosine),
+        AnyPoint::new(4.0, 100.0, SegmentShape::Step(Jump::At(0.5))),
+        AnyPoint::new(6.0, 200.0, SegmentShape::Linear),
+    ].into();
+    let tempo = TempoMap::new(&tempo_env).unwrap();
+    let beats: VecEnvelope<EasePoint<f64, f64>> = vec![
+        EasePoint::new(-1.0, -1.0, None),
+        EasePoint::new(8.0, 8.0, None),
+    ].into_iter().collect();
+    for (i, y) in tempo.steps(&beats, -0.5, 1.0 / 64.0).take(300).enumerate() {
+        let beat = tempo.beat_at(-0.5 + i as f64 / 64.0);
+        assert!((y - beat.clamp(-1.0, 8.0)).abs() < 1e-9, "step {}: {} != {}", i, y, beat);
+    }
+
+    let empty: VecEnvelope<EasePoint<f64, f64>> = VecEnvelope::default();
+    assert!(TempoMap::new(&empty).is_none());
+}
+
+#[test]
+fn tempos_must_be_positive_and_finite() {
+    for &bpm in [0.0, -60.0, f64::INFINITY, f64::NAN].iter() {
+        let env: VecEnvelope<EasePoint<f64, f64>> = vec![
+            EasePoint::new(0.0, 120.0, None),
+            EasePoint::new(4.0, bpm, None),
+        ].into_iter().collect();
+        assert!(TempoMap::new(&env).is_none(), "bpm = {}", bpm);
+    }
+}
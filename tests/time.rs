@@ -0,0 +1,69 @@
+extern crate envelope;
+
+use envelope::{BezierPoint, EasePoint, Envelope, Point, SampleClock, Time, TimeUnit, VecEnvelope};
+use std::time::Duration;
+
+#[test]
+fn clock() {
+    let clock = SampleClock::milliseconds(48_000.0);
+    assert_eq!(clock.x_at::<f64>(48), 1.0);
+    assert_eq!(clock.x_at::<f32>(96_000), 2_000.0);
+    assert_eq!(clock.x_at::<Duration>(24_000), Duration::from_millis(500));
+    assert_eq!(clock.sample_at(&1.5f64), 72.0);
+    assert_eq!(clock.sample_at(&Duration::from_secs(2)), 96_000.0);
+    assert_eq!(SampleClock::seconds(48_000.0).sample_at(&1.5f64), 72_000.0);
+    assert_eq!(0.25f64.to_secs(TimeUnit::Milliseconds), 0.00025);
+}
+
+#[test]
+fn render_milliseconds() {
+    // A 10ms fade in.
+    let env: VecEnvelope<EasePoint<f64, f64>> =
+        vec![EasePoint::new(0.0, 0.0, None), EasePoint::new(10.0, 1.0, None)].into_iter().collect();
+    let mut out = [0.0; 8];
+    assert_eq!(env.render_samples(SampleClock::milliseconds(400.0), 1, &mut out), 4);
+    assert_eq!(&out[..4], &[0.25, 0.5, 0.75, 1.0]);
+}
+
+#[test]
+fn clock_steps() {
+    let env: VecEnvelope<EasePoint<f64, f64>> =
+        vec![EasePoint::new(0.0, 0.0, None), EasePoint::new(10.0, 1.0, None)].into_iter().collect();
+    let clock = SampleClock::milliseconds(400.0);
+    let mut steps = env.clock_steps(2, clock);
+    assert_eq!(steps.clock(), clock);
+    assert_eq!(steps.x_at(4), 10.0);
+    assert_eq!(steps.next(), Some(0.5));
+    assert_eq!(steps.sample(), 3);
+    steps.set_sample(4);
+    assert_eq!(steps.collect::<Vec<_>>(), vec![1.0]);
+
+    // Envelopes with any `NumCast` `X` may still be sampled at a plain sample rate.
+    let frames: VecEnvelope<BezierPoint<i32, f32>> =
+        vec![BezierPoint::new(0, 0.0, 0.0), BezierPoint::new(4, 1.0, 0.0)].into_iter().collect();
+    assert_eq!(frames.sample_steps(0, 1.0).collect::<Vec<_>>(), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+}
+
+#[test]
+fn render_durations() {
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct P {
+        x: Duration,
+        y: f64,
+    }
+    impl Point for P {
+        type X = Duration;
+        type Y = f64;
+        fn x_to_scalar(x: Duration) -> f64 { x.as_secs_f64() }
+        fn x(&self) -> Duration { self.x }
+        fn y(&self) -> f64 { self.y }
+    }
+
+    let env: VecEnvelope<P> = vec![
+        P { x: Duration::from_secs(0), y: 1.0 },
+        P { x: Duration::from_secs(2), y: 0.0 },
+    ].into_iter().collect();
+    let mut out = [0.0; 6];
+    assert_eq!(env.render_samples(SampleClock::seconds(2.0), 0, &mut out), 5);
+    assert_eq!(&out[..5], &[1.0, 0.75, 0.5, 0.25, 0.0]);
+}
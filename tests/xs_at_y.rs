@@ -0,0 +1,93 @@
+extern crate envelope;
+
+mod common;
+
+use common::sampled_crossings;
+use envelope::{BezierPoint, EasePoint, Envelope, VecEnvelope};
+use envelope::interpolation::EaseFunction;
+
+fn linear(points: &[(f64, f64)]) -> VecEnvelope<EasePoint<f64, f64>> {
+    points.iter().map(|&(x, y)| EasePoint::new(x, y, None)).collect()
+}
+
+#[test]
+fn linear_segments() {
+    let env = linear(&[(0.0, 0.0), (2.0, 1.0), (4.0, 0.0), (6.0, 1.0)]);
+    assert_eq!(env.xs_at_y(0.5).collect::<Vec<_>>(), vec![1.0, 3.0, 5.0]);
+    // Crossings that land on points are only reported once.
+    assert_eq!(env.xs_at_y(1.0).collect::<Vec<_>>(), vec![2.0, 6.0]);
+    assert_eq!(env.xs_at_y(0.0).collect::<Vec<_>>(), vec![0.0, 4.0]);
+    assert_eq!(env.xs_at_y(2.0).count(), 0);
+}
+
+#[test]
+fn flat_runs_and_jumps() {
+    let env = linear(&[(0.0, 0.0), (1.0, 0.5), (2.0, 0.5), (3.0, 0.5), (4.0, 1.0), (4.0, 0.0)]);
+    // Only the start of the flat run from 1 to 3 is reported.
+    assert_eq!(env.xs_at_y(0.5).collect::<Vec<_>>(), vec![1.0, 4.0]);
+    assert_eq!(env.xs_at_y(0.75).collect::<Vec<_>>(), vec![3.5, 4.0]);
+
+    let single = linear(&[(1.0, 0.5)]);
+    assert_eq!(single.xs_at_y(0.5).collect::<Vec<_>>(), vec![1.0]);
+    assert_eq!(single.xs_at_y(0.0).count(), 0);
+}
+
+#[test]
+fn bezier_segments() {
+    let env: VecEnvelope<BezierPoint<f64, f64>> = vec![
+        BezierPoint::new(0.0, 0.0, 0.75),
+        BezierPoint::new(1.0, 1.0, -0.5),
+        BezierPoint::new(3.0, 0.0, 2.0),
+        BezierPoint::new(4.0, 0.5, 0.0),
+    ].into_iter().collect();
+    for &y in &[0.1, 0.25, 0.4, 0.9] {
+        let xs: Vec<f64> = env.xs_at_y(y).collect();
+        assert_eq!(xs.len(), sampled_crossings(&env, y, 0.0, 4.0), "y = {}", y);
+        for x in xs {
+            assert!((env.y(x).unwrap() - y).abs() < 1e-9, "y = {}, x = {}", y, x);
+        }
+    }
+}
+
+#[test]
+fn ease_segments() {
+    let env: VecEnvelope<EasePoint<f64, f64>> = vec![
+        EasePoint::new(0.0, 0.0, Some(EaseFunction::BackOut)),
+        EasePoint::new(1.0, 1.0, Some(EaseFunction::BounceIn)),
+        EasePoint::new(2.0, 0.0, Some(EaseFunction::SineInOut)),
+        EasePoint::new(3.0, 1.0, None),
+    ].into_iter().collect();
+    for &y in &[0.05, 0.5, 0.9, 1.05] {
+        let xs: Vec<f64> = env.xs_at_y(y).collect();
+        assert_eq!(xs.len(), sampled_crossings(&env, y, 0.0, 3.0), "y = {}", y);
+        for x in xs {
+            assert!((env.y(x).unwrap() - y).abs() < 1e-9, "y = {}, x = {}", y, x);
+        }
+    }
+}
+
+#[test]
+fn unknown_shape_is_solved_numerically() {
+    use envelope::Point;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct P {
+        x: f64,
+        y: f64,
+    }
+    impl Point for P {
+        type X = f64;
+        type Y = f64;
+        fn x_to_scalar(x: f64) -> f64 { x }
+        fn x(&self) -> f64 { self.x }
+        fn y(&self) -> f64 { self.y }
+    }
+
+    let env: VecEnvelope<P> = vec![P { x: 0.0, y: 0.0 }, P { x: 4.0, y: 1.0 }, P { x: 8.0, y: 0.0 }]
+        .into_iter()
+        .collect();
+    let xs: Vec<f64> = env.xs_at_y(0.25).collect();
+    assert_eq!(xs.len(), 2);
+    assert!((xs[0] - 1.0).abs() < 1e-12);
+    assert!((xs[1] - 7.0).abs() < 1e-12);
+}